@@ -0,0 +1,107 @@
+use std::{
+    fs::File,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use flate2::read::GzDecoder;
+
+/// Archive formats that can be searched instead of a root directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Tar,
+    /// Gzipped tarballs, including `.crate` files
+    TarGz,
+    Zip,
+}
+
+impl Format {
+    /// The format of an archive by its file name, or `None` if it is not an archive
+    pub fn detect(path: &Path) -> Option<Format> {
+        let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+        if file_name.ends_with(".tar") {
+            Some(Format::Tar)
+        } else if file_name.ends_with(".tar.gz")
+            || file_name.ends_with(".tgz")
+            || file_name.ends_with(".crate")
+        {
+            Some(Format::TarGz)
+        } else if file_name.ends_with(".zip") {
+            Some(Format::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// A file in an archive
+#[derive(Debug)]
+pub struct Member {
+    /// Path inside of the archive
+    pub path: PathBuf,
+    pub contents: Vec<u8>,
+}
+
+/// Reads the files of an archive into memory - directories, links and other special members are left out
+pub fn members(path: &Path, format: Format) -> Result<Vec<Member>> {
+    let file = File::open(path).wrap_err_with(|| format!("Could not read {}", path.display()))?;
+
+    let members = match format {
+        Format::Tar => tar_members(file),
+        Format::TarGz => tar_members(GzDecoder::new(file)),
+        Format::Zip => zip_members(file),
+    };
+    members.wrap_err_with(|| format!("Could not read the archive {}", path.display()))
+}
+
+fn tar_members(reader: impl Read) -> Result<Vec<Member>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut members = vec![];
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = normalize(&entry.path()?);
+        let mut contents = vec![];
+        entry.read_to_end(&mut contents)?;
+        members.push(Member { path, contents });
+    }
+
+    Ok(members)
+}
+
+fn zip_members(file: File) -> Result<Vec<Member>> {
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut members = vec![];
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        // Paths escaping the archive (i.e. `../`) are left out as well
+        let path = match entry.enclosed_name() {
+            Some(path) if entry.is_file() => normalize(path),
+            _ => continue,
+        };
+
+        let mut contents = vec![];
+        entry.read_to_end(&mut contents)?;
+        members.push(Member { path, contents });
+    }
+
+    Ok(members)
+}
+
+/// Keeps only the normal components of a path in an archive, i.e. strips leading `./` and `/`
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
+/// Path TODOs in an archive member are reported by, i.e. `release.tar.gz!/src/main.rs`
+pub fn member_path(archive: &Path, member: &Path) -> PathBuf {
+    PathBuf::from(format!("{}!/{}", archive.display(), member.display()))
+}
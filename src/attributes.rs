@@ -0,0 +1,176 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// Attributes that skip a file when set, unless `todo-ci` is set
+const SKIPPED_ATTRIBUTES: &[&str] = &["linguist-generated", "linguist-vendored"];
+
+/// Attribute to explicitly search (`todo-ci`) or skip (`-todo-ci` / `todo-ci=false`) a file
+const TODO_CI_ATTRIBUTE: &str = "todo-ci";
+
+/// A line of a `.gitattributes` file
+#[derive(Debug)]
+struct Line {
+    pattern: Gitignore,
+    /// Attribute names and whether they are set - `None` for `!attribute`, which unspecifies it
+    attributes: Vec<(String, Option<bool>)>,
+}
+
+/// Reads `.gitattributes` files to skip generated & vendored files, or those marked with `-todo-ci`
+///
+/// `.gitattributes` files are read from the directories of a file up to the repository root, or the root
+/// directory if it's not inside of a repository - deeper files and later lines take precedence.
+#[derive(Debug)]
+pub struct GitAttributes {
+    root_directory: PathBuf,
+    /// Directory to stop reading `.gitattributes` files at
+    top_directory: PathBuf,
+    /// Lines of the `.gitattributes` file by the directory it is in
+    files: HashMap<PathBuf, Vec<Line>>,
+    /// Whether files missing from `files` are read from disk, rather than being absent
+    read_files: bool,
+}
+
+impl GitAttributes {
+    pub fn new(root_directory: &Path) -> Result<GitAttributes> {
+        let root_directory = root_directory
+            .canonicalize()
+            .wrap_err_with(|| format!("{} does not exist", root_directory.display()))?;
+        let top_directory = root_directory
+            .ancestors()
+            .find(|directory| directory.join(".git").exists())
+            .unwrap_or(&root_directory)
+            .to_path_buf();
+
+        Ok(GitAttributes {
+            root_directory,
+            top_directory,
+            files: HashMap::new(),
+            read_files: true,
+        })
+    }
+
+    /// Uses the `.gitattributes` files of a tree instead, by their path relative to the root directory
+    ///
+    /// Like the ignore files of a tree, only those inside of the root directory apply.
+    pub fn from_files(
+        root_directory: &Path,
+        attributes_files: Vec<(PathBuf, Vec<u8>)>,
+    ) -> GitAttributes {
+        let files = attributes_files
+            .into_iter()
+            .map(|(path, contents)| {
+                let directory = root_directory.join(path.parent().unwrap_or_else(|| Path::new("")));
+                let lines = parse(&String::from_utf8_lossy(&contents), &directory);
+                (directory, lines)
+            })
+            .collect();
+
+        GitAttributes {
+            root_directory: root_directory.to_path_buf(),
+            top_directory: root_directory.to_path_buf(),
+            files,
+            read_files: false,
+        }
+    }
+
+    /// The attribute a file relative to the root directory is skipped for and the `.gitattributes` file
+    /// that sets it, or `None` if it is searched
+    pub fn skipped_by(&mut self, relative_path: &Path) -> Option<(String, PathBuf)> {
+        let path = self.root_directory.join(relative_path);
+
+        let mut directories: Vec<PathBuf> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|directory| directory.starts_with(&self.top_directory))
+            .map(PathBuf::from)
+            .collect();
+        // Shallower files are applied first, so deeper ones override them
+        directories.reverse();
+
+        let mut states: HashMap<String, (Option<bool>, PathBuf)> = HashMap::new();
+        for directory in directories {
+            let attributes_file = directory.join(".gitattributes");
+            let read_files = self.read_files;
+            let lines = self.files.entry(directory).or_insert_with(|| {
+                if read_files {
+                    read(&attributes_file)
+                } else {
+                    vec![]
+                }
+            });
+
+            for line in lines.iter() {
+                if !line.pattern.matched(&path, false).is_ignore() {
+                    continue;
+                }
+                for (name, state) in &line.attributes {
+                    states.insert(name.clone(), (*state, attributes_file.clone()));
+                }
+            }
+        }
+
+        match states.remove(TODO_CI_ATTRIBUTE) {
+            Some((Some(false), file)) => return Some((format!("-{}", TODO_CI_ATTRIBUTE), file)),
+            Some((Some(true), _)) => return None,
+            _ => (),
+        }
+        SKIPPED_ATTRIBUTES
+            .iter()
+            .find_map(|attribute| match states.remove(*attribute) {
+                Some((Some(true), file)) => Some((attribute.to_string(), file)),
+                _ => None,
+            })
+    }
+}
+
+/// Reads the lines of a `.gitattributes` file - a missing or unreadable file has none
+fn read(path: &Path) -> Vec<Line> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => parse(&contents, path.parent().unwrap_or(path)),
+        Err(_) => vec![],
+    }
+}
+
+/// Parses the lines of a `.gitattributes` file in `directory`
+fn parse(contents: &str, directory: &Path) -> Vec<Line> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next()?;
+            // Negative patterns are not allowed in `.gitattributes`
+            if pattern.starts_with('#') || pattern.starts_with('!') {
+                return None;
+            }
+
+            let mut builder = GitignoreBuilder::new(directory);
+            builder.add_line(None, pattern).ok()?;
+            let pattern = builder.build().ok()?;
+
+            let attributes = parts.map(parse_attribute).collect();
+            Some(Line {
+                pattern,
+                attributes,
+            })
+        })
+        .collect()
+}
+
+/// Parses `attribute`, `-attribute`, `!attribute` or `attribute=value` - where only `false` unsets it
+fn parse_attribute(attribute: &str) -> (String, Option<bool>) {
+    if let Some(name) = attribute.strip_prefix('-') {
+        return (name.to_string(), Some(false));
+    }
+    if let Some(name) = attribute.strip_prefix('!') {
+        return (name.to_string(), None);
+    }
+    match attribute.split_once('=') {
+        Some((name, value)) => (name.to_string(), Some(value != "false")),
+        None => (attribute.to_string(), Some(true)),
+    }
+}
@@ -1,29 +1,119 @@
 use std::path::PathBuf;
 
 use chrono::FixedOffset;
-use clap::{builder::TypedValueParser, error::ErrorKind, Parser, ValueEnum};
+use clap::{builder::TypedValueParser, error::ErrorKind, Parser, Subcommand, ValueEnum};
 use grep::{
     matcher::{Captures, Matcher},
     regex::RegexMatcher,
 };
 
+use crate::encoding::EncodingMode;
+
 /// todo-ci: A simple ci tool to check overdue todos
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// For disabling ignored files by default (.gitignore, hidden files, etc.)
-    #[arg(short = 'n', long = "no-ignore")]
+    #[arg(short = 'n', long = "no-ignore", global = true)]
     pub no_ignore: bool,
 
-    /// For disabling returning system error code (1) if there are overdue todos
+    /// For searching hidden files
+    #[arg(long = "hidden", global = true)]
+    pub hidden: bool,
+
+    /// For disabling .gitignore & .git/info/exclude files
+    #[arg(long = "no-gitignore", global = true)]
+    pub no_gitignore: bool,
+
+    /// For disabling the global gitignore file
+    #[arg(long = "no-global-ignore", global = true)]
+    pub no_global_ignore: bool,
+
+    /// For disabling ignore files of parent directories
+    #[arg(long = "no-parent-ignore", global = true)]
+    pub no_parent_ignore: bool,
+
+    /// For searching files marked as generated or vendored (or with `-todo-ci`) in .gitattributes
+    #[arg(long = "no-gitattributes", global = true)]
+    pub no_gitattributes: bool,
+
+    /// For following symbolic links
+    #[arg(long = "follow-symlinks", global = true)]
+    pub follow_symlinks: bool,
+
+    /// Maximum depth of directories to search in - the root directory is depth 0
+    #[arg(long = "max-depth", global = true)]
+    pub max_depth: Option<usize>,
+
+    /// For not searching directories on other file systems than the root directory
+    #[arg(long = "one-file-system", global = true)]
+    pub one_file_system: bool,
+
+    /// How git submodules & other nested repositories inside of the root directory are searched: {n}
+    ///- include: as part of the root directory {n}
+    ///- skip: not at all {n}
+    ///- separate: as separate projects, with their own `.todo-ci.toml` {n}
+    #[arg(
+        value_enum,
+        rename_all = "kebab_case",
+        long = "nested-repos",
+        default_value = "include",
+        global = true
+    )]
+    pub nested_repos: NestedRepos,
+
+    /// Commit to search the files of instead of the working directory (i.e. `v1.4.0`)
+    #[arg(long = "rev", global = true)]
+    pub revision: Option<String>,
+
+    /// For searching the staged contents of files instead of the working directory
+    #[arg(long = "staged", conflicts_with = "revision", global = true)]
+    pub staged: bool,
+
+    /// File to read the paths of the files to search from instead of walking the root directory, `-` for stdin -
+    /// one path per line or NUL-delimited. Ignore files don't apply to them
+    #[arg(long = "files-from", conflicts_with_all = ["revision", "staged"])]
+    pub files_from: Option<PathBuf>,
+
+    /// File name to report the contents read from stdin by, with `-` as the root directory (i.e. `src/main.rs`)
+    #[arg(long = "stdin-filename")]
+    pub stdin_filename: Option<PathBuf>,
+
+    /// Ignore files in gitignore format in addition to `.tdignore` (i.e. `.todoignore`)
+    #[arg(long = "ignore-file", global = true)]
+    pub ignore_file_names: Vec<String>,
+
+    /// For disabling returning a failing exit code if there are todos that fail the build
     #[arg(short = 'e', long = "no-error")]
     pub no_error: bool,
 
+    /// Todos that fail the build, with their exit code:
+    ///{n}
+    ///- overdue: error-level overdue todos (1) {n}
+    ///- malformed: error-level malformed todos (3) {n}
+    ///- due-soon: todos that are due within `--due-soon` days (4) {n}
+    #[arg(
+        value_enum,
+        long = "fail-on",
+        value_delimiter = ',',
+        default_value = "overdue"
+    )]
+    pub fail_on: Vec<FailOn>,
+
+    /// Number of days before their deadline that todos are due soon
+    #[arg(long = "due-soon", default_value = "7")]
+    pub due_soon_days: i64,
+
     /// Display mode:
     ///{n}
     ///- concise: total number of valid + overdue todos {n}
     ///- overdue-only: total number of valid + overdue todos + details of overdue todos {n}
     ///- default: total number of valid + overdue todos + details of all todos {n}
+    ///- verbose: default + the path rules that apply to each todo {n}
+    ///- json: all todos with the path rules that apply to them, statistics, suppressions & policy violations as JSON {n}
     #[arg(
         value_enum,
         rename_all = "kebab_case",
@@ -33,16 +123,75 @@ pub struct Args {
     )]
     pub display_mode: DisplayMode,
 
-    /// Root directory to check `todos` for
+    /// Root directories to check `todos` for, reported together - or `.tar`, `.tar.gz`, `.zip` or `.crate` archives,
+    /// or `-` to read a single file from stdin (see `--stdin-filename`)
     #[arg(value_parser, default_value = "./")]
-    pub root_directory: PathBuf,
+    pub root_directories: Vec<PathBuf>,
+
+    /// For checking each package of the Cargo, npm or pnpm workspace in the root directories instead,
+    /// reported by package
+    #[arg(long = "workspace")]
+    pub workspace: bool,
 
-    /// Pattern to check `todos` for (i.e. `*.rs` , `main.*`, etc.)
-    #[arg(short = 'p', long = "pattern", value_parser, default_value = "*")]
-    pub ignore_pattern: String,
+    /// Globs of files to check `todos` for, relative to the root directory (i.e. `*.rs`, `src/`) - all files if not set
+    #[arg(
+        short = 'p',
+        long = "include",
+        visible_alias = "pattern",
+        global = true
+    )]
+    pub include: Vec<String>,
+
+    /// Globs of files not to check `todos` for, relative to the root directory (i.e. `generated/`, `*.pb.rs`)
+    #[arg(long = "exclude", global = true)]
+    pub exclude: Vec<String>,
+
+    /// File types to check `todos` for, as defined by ripgrep (i.e. `rust,python`)
+    #[arg(long = "type", value_delimiter = ',', global = true)]
+    pub file_types: Vec<String>,
     /// Timezone to use for date checking
     #[arg(short = 't', long = "timezone-offset", value_parser = FixedOffsetParser, default_value = "+00:00", allow_hyphen_values = true)]
     pub timezone_offset: FixedOffset,
+
+    /// Encoding of scanned files:
+    ///{n}
+    ///- auto: detect a BOM, UTF-8, UTF-16 or fall back to Latin-1 {n}
+    ///- lossy: UTF-8, replacing invalid sequences {n}
+    ///- any other encoding label (i.e. `utf-16le`, `latin1`, `shift_jis`) {n}
+    #[arg(
+        long = "encoding",
+        value_parser = EncodingModeParser,
+        default_value = "auto",
+        global = true
+    )]
+    pub encoding: EncodingMode,
+
+    /// For matching todos anywhere in a file instead of only inside of comments
+    #[arg(long = "anywhere")]
+    pub anywhere: bool,
+
+    /// Configuration file to use instead of `.todo-ci.toml` in the root directory
+    #[arg(short = 'c', long = "config", value_parser, global = true)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List every file that would be searched for todos
+    Files {
+        /// Root directory to list files of
+        #[arg(value_parser, default_value = "./")]
+        root_directory: PathBuf,
+    },
+    /// Explain why a file is or isn't searched for todos
+    Explain {
+        /// File to explain
+        #[arg(value_parser)]
+        path: PathBuf,
+        /// Root directory the file would be searched in
+        #[arg(value_parser, default_value = "./")]
+        root_directory: PathBuf,
+    },
 }
 
 #[derive(ValueEnum, Debug, Clone)]
@@ -50,7 +199,22 @@ pub enum DisplayMode {
     Concise,
     OverdueOnly,
     Default,
-    // Verbose,
+    Verbose,
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, PartialEq, Eq)]
+pub enum FailOn {
+    Overdue,
+    Malformed,
+    DueSoon,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NestedRepos {
+    Include,
+    Skip,
+    Separate,
 }
 
 #[derive(Clone)]
@@ -99,3 +263,22 @@ impl TypedValueParser for FixedOffsetParser {
         }
     }
 }
+
+#[derive(Clone)]
+struct EncodingModeParser;
+
+impl TypedValueParser for EncodingModeParser {
+    type Value = EncodingMode;
+
+    fn parse_ref(
+        &self,
+        _cmd: &clap::Command,
+        _arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<EncodingMode, clap::Error> {
+        let label = value.to_str().expect("Should be string!");
+
+        EncodingMode::from_label(label)
+            .map_err(|err| clap::Error::raw(ErrorKind::ValueValidation, err.to_string()))
+    }
+}
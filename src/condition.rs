@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+
+use eyre::{eyre, Result};
+use semver::VersionReq;
+
+use crate::project::Project;
+
+/// A non-date trigger that makes a TODO overdue once it is met, i.e. `version>=2.0.0`, `dep:serde>=2.0` or `when-exists:src/new_api.rs`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    /// The condition as written in the TODO
+    pub text: String,
+    /// The value the condition was checked against, i.e. the current project or dependency version,
+    /// whether a path `exists` or the state of an issue
+    pub current: String,
+    pub met: bool,
+}
+
+/// The kinds of conditions, parsed from the TODO
+#[derive(Debug)]
+enum Trigger {
+    /// `version<requirement>` - the project's own version matches the requirement
+    Version(VersionReq),
+    /// `dep:<name><requirement>` - the resolved version of a dependency matches the requirement
+    Dependency {
+        name: String,
+        requirement: VersionReq,
+    },
+    /// `rust<requirement>` - the project's minimum supported Rust version matches the requirement
+    Rust(VersionReq),
+    /// `when-exists:<path>` or `when-missing:<path>` - a path relative to the root directory exists or not
+    Path { path: PathBuf, exists: bool },
+    /// `issue:#<number>` - the issue is closed
+    Issue(u64),
+}
+
+impl Trigger {
+    /// Parses a condition, returning `None` if `text` is not a condition (i.e. a date)
+    fn parse(text: &str) -> Option<Result<Trigger>> {
+        if let Some(requirement) = text.strip_prefix("version") {
+            return Some(parse_requirement(requirement).map(Trigger::Version));
+        }
+
+        if let Some(requirement) = text.strip_prefix("rust") {
+            return Some(parse_requirement(requirement).map(Trigger::Rust));
+        }
+
+        for (prefix, exists) in [("when-exists:", true), ("when-missing:", false)] {
+            if let Some(path) = text.strip_prefix(prefix) {
+                let path = path.trim();
+                if path.is_empty() {
+                    return Some(Err(eyre!("the path is missing")));
+                }
+
+                return Some(Ok(Trigger::Path {
+                    path: path.into(),
+                    exists,
+                }));
+            }
+        }
+
+        if let Some(issue) = text.strip_prefix("issue:") {
+            let issue = issue.trim();
+            return Some(
+                issue
+                    .trim_start_matches('#')
+                    .parse()
+                    .map(Trigger::Issue)
+                    .map_err(|_| eyre!("`{}` is not an issue number, i.e. `#123`", issue)),
+            );
+        }
+
+        if let Some(dependency) = text.strip_prefix("dep:") {
+            let name_end = dependency
+                .find(['<', '>', '=', '~', '^'])
+                .unwrap_or(dependency.len());
+            let name = dependency[..name_end].trim();
+            if name.is_empty() {
+                return Some(Err(eyre!("the dependency name is missing")));
+            }
+
+            return Some(
+                parse_requirement(&dependency[name_end..]).map(|requirement| Trigger::Dependency {
+                    name: name.to_string(),
+                    requirement,
+                }),
+            );
+        }
+
+        None
+    }
+
+    /// Checks the condition, returning the current value and whether the condition is met
+    fn check(&self, project: &mut Project) -> Result<(String, bool)> {
+        match self {
+            Trigger::Version(requirement) => {
+                let version = project.version()?;
+                Ok((version.to_string(), requirement.matches(&version)))
+            }
+            Trigger::Dependency { name, requirement } => {
+                let version = project.dependency_version(name)?;
+                Ok((version.to_string(), requirement.matches(&version)))
+            }
+            Trigger::Rust(requirement) => {
+                let version = project.rust_version()?;
+                Ok((version.to_string(), requirement.matches(&version)))
+            }
+            Trigger::Path { path, exists } => {
+                let current = project.path_exists(path);
+                let description = if current { "exists" } else { "missing" };
+                Ok((description.to_string(), current == *exists))
+            }
+            Trigger::Issue(number) => {
+                let state = project.issue_state(*number)?;
+                let closed = state == "closed";
+                Ok((state, closed))
+            }
+        }
+    }
+}
+
+impl Condition {
+    /// Whether `text` is written as a condition rather than a date
+    pub fn is_condition(text: &str) -> bool {
+        Trigger::parse(text).is_some()
+    }
+
+    /// Parses and checks a condition against the project
+    ///
+    /// Returns `None` if `text` is not a condition, or an error describing why the condition is malformed
+    pub fn check(text: &str, project: &mut Project) -> Option<Result<Condition>> {
+        let trigger = match Trigger::parse(text)? {
+            Ok(trigger) => trigger,
+            Err(err) => return Some(Err(err)),
+        };
+
+        Some(trigger.check(project).map(|(current, met)| Condition {
+            text: text.to_string(),
+            current,
+            met,
+        }))
+    }
+}
+
+fn parse_requirement(requirement: &str) -> Result<VersionReq> {
+    let requirement = requirement.trim();
+    if !requirement.starts_with(['<', '>', '=', '~', '^']) {
+        return Err(eyre!(
+            "`{}` should start with a comparison operator, i.e. `>=2.0.0`",
+            requirement
+        ));
+    }
+
+    VersionReq::parse(requirement)
+        .map_err(|_| eyre!("`{}` is not a valid version requirement", requirement))
+}
@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+use serde::Deserialize;
+
+use crate::{
+    issue::IssueSource,
+    policy::{Budget, Ratchet},
+    rules::PathRule,
+    severity::SeverityPolicy,
+    suppression::SuppressionPolicy,
+};
+
+/// Project configuration, read from a `.todo-ci.toml` file in the root directory
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Additional `strftime` formats for TODO dates (i.e. `%d/%m/%Y`)
+    pub date_formats: Vec<String>,
+    /// File to read the project version from for `version` conditions, instead of a known manifest
+    pub version_file: Option<PathBuf>,
+    /// Regex with a `version` capture group to find the version in `version-file`
+    pub version_pattern: Option<String>,
+    /// Where to read the states of issues from for `issue` conditions
+    pub issues: IssueSource,
+    /// Severities of overdue & malformed TODOs that don't have one of their own
+    pub severity: SeverityPolicy,
+    /// Maximum numbers of TODOs
+    pub budget: Budget,
+    /// Prevents the numbers of TODOs from increasing
+    pub ratchet: Ratchet,
+    /// Overrides for TODOs in paths matching a glob
+    pub rules: Vec<PathRule>,
+    /// How inline suppression comments are checked
+    pub suppressions: SuppressionPolicy,
+}
+
+impl Config {
+    pub const FILE_NAME: &'static str = ".todo-ci.toml";
+
+    /// Loads the configuration from `config_path`, or else from the root directory if it exists there
+    pub fn load(root_directory: &Path, config_path: Option<&Path>) -> Result<Config> {
+        let config_path: PathBuf = match config_path {
+            Some(config_path) => config_path.into(),
+            None => {
+                let default_path = root_directory.join(Config::FILE_NAME);
+                if !default_path.is_file() {
+                    return Ok(Config::default());
+                }
+                default_path
+            }
+        };
+
+        let contents = std::fs::read_to_string(&config_path)
+            .wrap_err_with(|| format!("Could not read {}", config_path.display()))?;
+
+        toml::from_str(&contents)
+            .wrap_err_with(|| format!("Could not parse {}", config_path.display()))
+    }
+}
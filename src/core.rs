@@ -1,22 +1,42 @@
 use std::{
-    cmp::Ordering,
+    ops::Range,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
 
-use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
-use eyre::Result;
-use globset::Glob;
+use chrono::{FixedOffset, Utc};
+use eyre::{eyre, Result};
 use grep::{
     matcher::{Captures, Matcher},
     regex::RegexMatcher,
     searcher::{sinks::UTF8, Searcher},
 };
-use ignore::{DirEntry, WalkBuilder};
+use ignore::{
+    types::{Types, TypesBuilder},
+    DirEntry, WalkBuilder,
+};
+
+use crate::{
+    cli::NestedRepos,
+    condition::Condition,
+    config::Config,
+    deadline::{Deadline, RelativeOffset},
+    encoding::{self, EncodingMode},
+    git,
+    issue::IssueSource,
+    language::{Comment, Language},
+    near_miss, paths,
+    project::{Project, VersionSource},
+    rules::{EffectiveRule, PathRule, RuleSet},
+    severity::{Severity, SeverityPolicy},
+    source::{self, SourceKind},
+    suppression::{self, Suppression, SuppressionPolicy},
+};
 
 #[derive(Debug)]
 /// Valid: TODO is valid
 /// Overdue: TODO is overdue
-/// Malformed: TODO has a malformed date format
+/// Malformed: TODO has a malformed date format or does not follow the TODO format
 pub enum TodoState {
     Valid,
     Overdue,
@@ -27,95 +47,533 @@ pub enum TodoState {
 pub struct Todo {
     pub file: PathBuf,
     pub line_number: i32,
-    pub date: Option<NaiveDate>,
+    /// Last line of the description, which may continue over several comment lines
+    pub end_line_number: i32,
+    pub deadline: Option<Deadline>,
+    /// Non-date condition of the TODO, instead of a deadline
+    pub condition: Option<Condition>,
     pub description: String,
+    /// Tags mentioned in the description, i.e. `perf` for `#perf`
+    pub tags: Vec<String>,
+    /// Owner mentioned in the description, i.e. `platform-team` for `@platform-team`
+    pub owner: Option<String>,
     pub state: TodoState,
+    /// Corrected annotation for a malformed TODO
+    pub suggestion: Option<String>,
+    /// Severity of an overdue or malformed TODO
+    pub severity: Option<Severity>,
+    /// Overrides of the path rules that match the TODO's file
+    pub rule: Option<EffectiveRule>,
+    /// Inline suppression comment the TODO is suppressed by
+    pub suppression: Option<Suppression>,
+    /// Nested repository the TODO's file is in, `None` for the root directory's repository
+    pub repository: Option<PathBuf>,
+    /// Root directory or workspace package the TODO was found in, when several are searched
+    pub package: Option<PathBuf>,
 }
 
 #[derive(Debug)]
 pub struct SearchResult {
     pub todos: Vec<Todo>,
+    /// TODOs suppressed by inline suppression comments - they don't count towards any statistics but their own
+    pub suppressed_todos: Vec<Todo>,
+    /// Inline suppression comments, used or not
+    pub suppressions: Vec<Suppression>,
     pub statistics: TodoStatistics,
+    /// Statistics of each root directory or workspace package, when several are searched -
+    /// `statistics` holds their totals
+    pub packages: Vec<PackageStatistics>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct TodoStatistics {
     pub files_searched: i32,
     pub valid_todo_count: i32,
     pub overdue_todo_count: i32,
     pub malformed_todo_count: i32,
+    pub suppressed_todo_count: i32,
 }
 
-pub fn search(
-    root_directory: PathBuf,
-    no_ignore: bool,
-    ignore_pattern: String,
-    fixed_offset: &FixedOffset,
-) -> Result<SearchResult> {
+#[derive(Debug)]
+pub struct PackageStatistics {
+    pub root_directory: PathBuf,
+    pub statistics: TodoStatistics,
+}
+
+impl TodoStatistics {
+    fn add(&mut self, other: &TodoStatistics) {
+        self.files_searched += other.files_searched;
+        self.valid_todo_count += other.valid_todo_count;
+        self.overdue_todo_count += other.overdue_todo_count;
+        self.malformed_todo_count += other.malformed_todo_count;
+        self.suppressed_todo_count += other.suppressed_todo_count;
+    }
+}
+
+/// Options for searching TODOs - combined from CLI arguments and the project configuration
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    /// For disabling ignored files by default (.gitignore, hidden files, etc.)
+    pub no_ignore: bool,
+    /// For searching hidden files
+    pub hidden: bool,
+    /// For disabling .gitignore & .git/info/exclude files
+    pub no_gitignore: bool,
+    /// For disabling the global gitignore file
+    pub no_global_ignore: bool,
+    /// For disabling ignore files of parent directories
+    pub no_parent_ignore: bool,
+    /// For searching files marked as generated or vendored in .gitattributes
+    pub no_gitattributes: bool,
+    pub follow_symlinks: bool,
+    /// Maximum depth of directories to search in - the root directory is depth 0
+    pub max_depth: Option<usize>,
+    /// For not searching directories on other file systems than the root directory
+    pub one_file_system: bool,
+    /// How git submodules & other nested repositories inside of the root directory are searched
+    pub nested_repos: NestedRepos,
+    /// Where the files to search are read from
+    pub source: SourceKind,
+    /// Ignore files in gitignore format in addition to `.tdignore` - which are never disabled
+    pub ignore_file_names: Vec<String>,
+    /// Globs of files to check `todos` for, relative to the root directory - all files if empty
+    pub include: Vec<String>,
+    /// Globs of files not to check `todos` for, relative to the root directory
+    pub exclude: Vec<String>,
+    /// ripgrep file types to check `todos` for (i.e. `rust`) - all files if empty
+    pub file_types: Vec<String>,
+    /// Timezone to use for date checking
+    pub fixed_offset: FixedOffset,
+    pub encoding_mode: EncodingMode,
+    /// For matching todos anywhere in a file instead of only inside of comments
+    pub anywhere: bool,
+    /// Additional `strftime` formats for TODO dates
+    pub date_formats: Vec<String>,
+    /// Where to read the project version from for `version` conditions
+    pub version_source: VersionSource,
+    /// Where to read issue states from for `issue` conditions
+    pub issue_source: IssueSource,
+    /// Severities of TODOs that don't have one of their own
+    pub severity_policy: SeverityPolicy,
+    /// Overrides for TODOs in paths matching a glob
+    pub rules: Vec<PathRule>,
+    /// How inline suppression comments are checked
+    pub suppression_policy: SuppressionPolicy,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            no_ignore: false,
+            hidden: false,
+            no_gitignore: false,
+            no_global_ignore: false,
+            no_parent_ignore: false,
+            no_gitattributes: false,
+            follow_symlinks: false,
+            max_depth: None,
+            one_file_system: false,
+            nested_repos: NestedRepos::Include,
+            source: SourceKind::WorkingDirectory,
+            ignore_file_names: vec![],
+            include: vec![],
+            exclude: vec![],
+            file_types: vec![],
+            fixed_offset: FixedOffset::east(0),
+            encoding_mode: EncodingMode::Auto,
+            anywhere: false,
+            date_formats: vec![],
+            version_source: VersionSource::default(),
+            issue_source: IssueSource::default(),
+            severity_policy: SeverityPolicy::default(),
+            rules: vec![],
+            suppression_policy: SuppressionPolicy::default(),
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Replaces the options that come from the project configuration
+    pub fn with_config(self, config: &Config) -> SearchOptions {
+        SearchOptions {
+            date_formats: config.date_formats.clone(),
+            version_source: VersionSource {
+                file: config.version_file.clone(),
+                pattern: config.version_pattern.clone(),
+            },
+            issue_source: config.issues.clone(),
+            severity_policy: config.severity.clone(),
+            rules: config.rules.clone(),
+            suppression_policy: config.suppressions.clone(),
+            ..self
+        }
+    }
+
+    /// Whether every file of the working directory is searched, so the numbers of TODOs can be compared
+    /// between runs - not just listed files, a filtered part of it or another tree
+    pub fn is_full_scan(&self) -> bool {
+        matches!(self.source, SourceKind::WorkingDirectory)
+            && self.include.is_empty()
+            && self.exclude.is_empty()
+            && self.file_types.is_empty()
+            && self.max_depth.is_none()
+    }
+
+    /// Names of the custom ignore files, in the order they take precedence - `.tdignore` is never disabled
+    pub fn custom_ignore_file_names(&self) -> Vec<String> {
+        let mut ignore_file_names = vec![".tdignore".to_string()];
+        ignore_file_names.extend(self.ignore_file_names.iter().cloned());
+        ignore_file_names
+    }
+
+    /// Matcher of the selected file types, or `None` if files of any type are searched
+    pub fn types(&self) -> Result<Option<Types>> {
+        if self.file_types.is_empty() {
+            return Ok(None);
+        }
+
+        let mut types = TypesBuilder::new();
+        types.add_defaults();
+        for file_type in &self.file_types {
+            types.select(file_type);
+        }
+        Ok(Some(types.build()?))
+    }
+
+    /// Options for searching a nested repository as a separate project, with its own configuration
+    /// and without the ignore files of the directories above it
+    pub fn for_nested_repository(&self, repository: &Path) -> Result<SearchOptions> {
+        let config = Config::load(repository, None)?;
+        Ok(SearchOptions {
+            no_parent_ignore: true,
+            ..self.clone().with_config(&config)
+        })
+    }
+}
+
+pub fn search(root_directory: PathBuf, options: &SearchOptions) -> Result<SearchResult> {
+    if !root_directory.exists() {
+        return Err(eyre!("{} does not exist", root_directory.display()));
+    }
+
     let mut todos: Vec<Todo> = vec![];
+    let mut suppressed_todos: Vec<Todo> = vec![];
+    let mut suppressions: Vec<Suppression> = vec![];
     let mut statistics = TodoStatistics {
         files_searched: 0,
         valid_todo_count: 0,
         overdue_todo_count: 0,
         malformed_todo_count: 0,
+        suppressed_todo_count: 0,
     };
 
-    let ignore_glob = Glob::new(&ignore_pattern)?.compile_matcher();
+    let rule_set = RuleSet::new(&options.rules)?;
+    let mut project = Project::new(
+        &root_directory,
+        &options.source,
+        options.version_source.clone(),
+        options.issue_source.clone(),
+    );
 
-    walk_files_and(
-        |file| {
-            if file.metadata()?.is_file()
-                && ignore_glob.is_match(file.path())
-                // Prevents .tdignore from being searched as well
-                // Not sure how this can be done more elegantly
-                && file.path().file_name().unwrap() != ".tdignore"
+    let nested_repositories = walk_texts_and(
+        |file_path, relative_path, text| {
+            let rule = rule_set.for_path(relative_path);
+            let file_search_result =
+                &mut search_todos(file_path, &text, options, rule.as_ref(), &mut project)?;
+            // Nested repositories are only walked into when they are included, and trees of a
+            // repository only hold its own files, as submodules are left out
+            let repository = match (&options.nested_repos, &options.source) {
+                (_, SourceKind::Revision(_) | SourceKind::Index) => None,
+                (NestedRepos::Include, _) => repository_of(&root_directory, relative_path),
+                (NestedRepos::Skip | NestedRepos::Separate, _) => None,
+            };
+            for todo in file_search_result
+                .todos
+                .iter_mut()
+                .chain(file_search_result.suppressed_todos.iter_mut())
             {
-                let file_search_result = &mut search_todos(file.path(), fixed_offset)?;
+                todo.rule = rule.clone();
+                todo.repository = repository.clone();
+            }
 
-                // Aggregate statistics
-                statistics.files_searched += 1;
-                statistics.valid_todo_count += file_search_result.statistics.valid_todo_count;
-                statistics.overdue_todo_count += file_search_result.statistics.overdue_todo_count;
-                statistics.malformed_todo_count +=
-                    file_search_result.statistics.malformed_todo_count;
+            // Aggregate statistics
+            statistics.add(&file_search_result.statistics);
 
-                // Aggregate TODOs
-                todos.append(&mut file_search_result.todos)
-            };
+            // Aggregate TODOs
+            todos.append(&mut file_search_result.todos);
+            suppressed_todos.append(&mut file_search_result.suppressed_todos);
+            suppressions.append(&mut file_search_result.suppressions);
             Ok(())
         },
-        root_directory,
-        no_ignore,
+        &root_directory,
+        options,
     )?;
+    project.write_issue_cache();
 
-    Ok(SearchResult { todos, statistics })
+    // Grouped by repository - the root directory's first
+    todos.sort_by_key(|todo| todo.repository.clone());
+    suppressed_todos.sort_by_key(|todo| todo.repository.clone());
+
+    if matches!(options.nested_repos, NestedRepos::Separate) {
+        for repository in nested_repositories {
+            let nested_options = options.for_nested_repository(&repository)?;
+            let mut nested_results = search(repository.clone(), &nested_options)?;
+            for todo in nested_results
+                .todos
+                .iter_mut()
+                .chain(nested_results.suppressed_todos.iter_mut())
+            {
+                // TODOs of repositories nested even deeper keep theirs
+                todo.repository.get_or_insert_with(|| repository.clone());
+            }
+
+            statistics.add(&nested_results.statistics);
+            todos.append(&mut nested_results.todos);
+            suppressed_todos.append(&mut nested_results.suppressed_todos);
+            suppressions.append(&mut nested_results.suppressions);
+        }
+    }
+
+    Ok(SearchResult {
+        todos,
+        suppressed_todos,
+        suppressions,
+        statistics,
+        packages: vec![],
+    })
+}
+
+/// Searches several root directories or workspace packages, each with their own options, into a single result
+///
+/// Packages inside of another one are left out of its search, so their TODOs are only counted once.
+pub fn search_packages(packages: Vec<(PathBuf, SearchOptions)>) -> Result<SearchResult> {
+    let mut results = SearchResult {
+        todos: vec![],
+        suppressed_todos: vec![],
+        suppressions: vec![],
+        statistics: TodoStatistics::default(),
+        packages: vec![],
+    };
+
+    let canonical_roots: Vec<PathBuf> = packages
+        .iter()
+        .map(|(root_directory, _)| {
+            root_directory
+                .canonicalize()
+                .unwrap_or_else(|_| root_directory.clone())
+        })
+        .collect();
+
+    for (index, (root_directory, mut options)) in packages.into_iter().enumerate() {
+        let canonical_root = &canonical_roots[index];
+        for other_root in &canonical_roots {
+            if let Ok(nested_package) = other_root.strip_prefix(canonical_root) {
+                if nested_package.as_os_str().is_empty() {
+                    continue;
+                }
+                options.exclude.push(format!(
+                    "{}/",
+                    paths::escape(&nested_package.to_string_lossy())
+                ));
+            }
+        }
+
+        let mut package_results = search(root_directory.clone(), &options)?;
+        for todo in package_results
+            .todos
+            .iter_mut()
+            .chain(package_results.suppressed_todos.iter_mut())
+        {
+            todo.package = Some(root_directory.clone());
+        }
+
+        results.statistics.add(&package_results.statistics);
+        results.todos.append(&mut package_results.todos);
+        results
+            .suppressed_todos
+            .append(&mut package_results.suppressed_todos);
+        results
+            .suppressions
+            .append(&mut package_results.suppressions);
+        results.packages.push(PackageStatistics {
+            root_directory,
+            statistics: package_results.statistics,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Lists the files that would be searched for TODOs
+pub fn files(root_directory: PathBuf, options: &SearchOptions) -> Result<Vec<PathBuf>> {
+    if !root_directory.exists() {
+        return Err(eyre!("{} does not exist", root_directory.display()));
+    }
+
+    let mut files = vec![];
+    let nested_repositories = walk_texts_and(
+        |file_path, _, _| {
+            files.push(file_path.to_path_buf());
+            Ok(())
+        },
+        &root_directory,
+        options,
+    )?;
+
+    if matches!(options.nested_repos, NestedRepos::Separate) {
+        for repository in nested_repositories {
+            let nested_options = options.for_nested_repository(&repository)?;
+            files.append(&mut self::files(repository, &nested_options)?);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Iterates over the files to search for TODOs and runs a function with their path,
+/// their path relative to the root directory and their decoded contents
+///
+/// Files are read from the source chosen by `options.source`, which applies the ignore files.
+/// Returns the nested repositories that were skipped, see `walk_files_and`
+fn walk_texts_and<F>(
+    mut f: F,
+    root_directory: &Path,
+    options: &SearchOptions,
+) -> Result<Vec<PathBuf>>
+where
+    F: FnMut(&Path, &Path, String) -> Result<()>,
+{
+    let include = paths::glob_set(&options.include)?;
+    let exclude = paths::glob_set(&options.exclude)?;
+
+    let mut source = source::open(root_directory, options);
+    source.files_and(&mut |file_path, relative_path, contents| {
+        if (options.include.is_empty() || include.is_match(relative_path))
+            && !exclude.is_match(relative_path)
+        {
+            // Files that can't be decoded as text are skipped rather than failing the search
+            if let Some(text) = encoding::decode(&contents, options.encoding_mode) {
+                f(file_path, relative_path, text)?;
+            }
+        }
+        Ok(())
+    })?;
+
+    Ok(source.nested_repositories())
 }
 
 /// Iterates over files in a specified directory and runs a function with the file as an arguement
-fn walk_files_and<F>(mut f: F, root_directory: PathBuf, no_ignore: bool) -> Result<()>
+///
+/// Only ignore files & file types are applied, not the include & exclude globs
+///
+/// Returns the nested repositories that were skipped, unless they are included with `NestedRepos::Include`
+pub fn walk_files_and<F>(
+    mut f: F,
+    root_directory: PathBuf,
+    options: &SearchOptions,
+) -> Result<Vec<PathBuf>>
 where
     F: FnMut(DirEntry) -> Result<()>,
 {
+    let ignore_file_names = options.custom_ignore_file_names();
+
     let mut builder = WalkBuilder::new(&root_directory);
+    builder
+        .standard_filters(!options.no_ignore)
+        .follow_links(options.follow_symlinks)
+        .max_depth(options.max_depth)
+        .same_file_system(options.one_file_system);
+    if let Some(types) = options.types()? {
+        builder.types(types);
+    }
+    if options.hidden {
+        builder.hidden(false);
+    }
+    if options.no_gitignore {
+        builder.git_ignore(false).git_exclude(false);
+    }
+    if options.no_global_ignore {
+        builder.git_global(false);
+    }
+    if options.no_parent_ignore {
+        builder.parents(false);
+    }
+    for ignore_file_name in &ignore_file_names {
+        builder.add_custom_ignore_filename(ignore_file_name);
+    }
+
+    let skip_nested_repositories = !matches!(options.nested_repos, NestedRepos::Include);
+    let nested_repositories = Arc::new(Mutex::new(vec![]));
+    let skipped_repositories = nested_repositories.clone();
+
+    // The custom ignore files themselves are not searched
     let walk = builder
-        .standard_filters(!no_ignore)
-        // Hard-coded
-        .add_custom_ignore_filename(".tdignore")
+        .filter_entry(move |entry| {
+            if skip_nested_repositories
+                && entry.depth() > 0
+                && matches!(entry.file_type(), Some(file_type) if file_type.is_dir())
+                && entry.path().join(".git").exists()
+            {
+                skipped_repositories
+                    .lock()
+                    .expect("Walker should not panic")
+                    .push(entry.path().to_path_buf());
+                return false;
+            }
+
+            !ignore_file_names
+                .iter()
+                .any(|ignore_file_name| entry.file_name() == ignore_file_name.as_str())
+        })
         .build();
 
     for file in walk.into_iter().filter_map(|file| file.ok()) {
         f(file)?;
     }
-    Ok(())
+
+    let mut nested_repositories = nested_repositories
+        .lock()
+        .expect("Walker should not panic")
+        .clone();
+    nested_repositories.sort();
+    Ok(nested_repositories)
+}
+
+/// The nested repository a file relative to the root directory is in, if any
+fn repository_of(root_directory: &Path, relative_path: &Path) -> Option<PathBuf> {
+    relative_path
+        .ancestors()
+        .skip(1)
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .map(|ancestor| root_directory.join(ancestor))
+        .find(|directory| directory.join(".git").exists())
 }
 
 /// Searches for TODOs in a file as well as their statistics
 ///
-/// Matches TODOs that follows the format: @todo(<date>):<description>
-fn search_todos(file_path: &Path, fixed_offset: &FixedOffset) -> Result<SearchResult> {
-    const PATTERN: &str = r"@todo\((?P<date>.{10})\):(?P<description>.*)";
+/// Matches TODOs made of `@todo`, a date in parentheses, a colon and a description
+///
+/// `text` is the already decoded contents of `file_path`
+///
+/// Only TODOs inside of comments are matched if the language of the file is known,
+/// unless `anywhere` is set
+///
+/// Conditions of TODOs are checked against `project`, and `rule` overrides the options for the file
+///
+/// TODOs on lines covered by inline suppression comments are returned separately as suppressed
+fn search_todos(
+    file_path: &Path,
+    text: &str,
+    options: &SearchOptions,
+    rule: Option<&EffectiveRule>,
+    project: &mut Project,
+) -> Result<SearchResult> {
+    const PATTERN: &str = r"@todo\((?P<date>[^()]+)\):(?P<description>.*)";
 
     let matcher = RegexMatcher::new_line_matcher(PATTERN)?;
+    // Lines are searched with a looser pattern, so near-misses of the format can be reported
+    let near_miss_matcher = RegexMatcher::new_line_matcher(near_miss::PATTERN)?;
+    let near_miss_capture_matcher = RegexMatcher::new(near_miss::CAPTURE_PATTERN)?;
 
     let mut searcher = Searcher::new();
     let mut todos: Vec<Todo> = vec![];
@@ -123,9 +581,32 @@ fn search_todos(file_path: &Path, fixed_offset: &FixedOffset) -> Result<SearchRe
     let mut overdue_todo_count = 0;
     let mut malformed_todo_count = 0;
 
-    searcher.search_path(
-        &matcher,
+    let lines: Vec<&str> = text.split('\n').collect();
+    // Only blamed once a relative deadline is found
+    let mut commit_dates = None;
+    // Staged files & stdin are blamed with their contents, not those of the file in the working directory
+    let (revision, contents) = match &options.source {
+        SourceKind::Revision(revision) => (Some(revision.as_str()), None),
+        SourceKind::Index | SourceKind::Stdin(_) => (None, Some(text)),
+        SourceKind::WorkingDirectory | SourceKind::FileList(_) => (None, None),
+    };
+    let now = Utc::now()
+        .with_timezone(&options.fixed_offset)
+        .naive_local();
+    let comments = Language::detect(file_path).map(|language| language.comments(text));
+    let severity_policy = rule
+        .and_then(|rule| rule.severity.as_ref())
+        .unwrap_or(&options.severity_policy);
+    let mut suppressions = suppression::find(
         file_path,
+        &lines,
+        comments.as_deref().filter(|_| !options.anywhere),
+        &options.suppression_policy,
+    );
+
+    searcher.search_slice(
+        &near_miss_matcher,
+        text.as_bytes(),
         UTF8(|lnum, line| {
             // Regex group match validation
             if matcher.capture_count() != 3
@@ -137,47 +618,170 @@ fn search_todos(file_path: &Path, fixed_offset: &FixedOffset) -> Result<SearchRe
                 return Ok(true);
             }
 
+            // Parts of the line that can contain a 'todo' - only its comments for known languages
+            let segments: Vec<(Range<usize>, Option<&Comment>)> = match &comments {
+                Some(comments) if !options.anywhere => comments[lnum as usize - 1]
+                    .iter()
+                    .map(|comment| (comment.range.clone(), Some(comment)))
+                    .collect(),
+                _ => vec![(0..line.len(), None)],
+            };
+
             // Parse date & description of 'todo'
             let mut captures = matcher.new_captures()?;
-            matcher.captures(line.as_bytes(), &mut captures)?;
+            let mut todo_segment = None;
+            for (segment, comment) in &segments {
+                if matcher.captures(line[segment.clone()].as_bytes(), &mut captures)? {
+                    todo_segment = Some((segment.clone(), *comment));
+                    break;
+                }
+            }
 
-            // Unwraps here are ok - as we've already verified 3 capture groups
-            let date_string = &line[captures.get(1).unwrap()];
-            let description_string = line[captures.get(2).unwrap()].trim();
-
-            // Validate date
-            let date = match NaiveDate::parse_from_str(date_string, "%Y-%m-%d") {
-                Ok(date) => fixed_offset.from_local_date(&date).unwrap(),
-                Err(_) => {
-                    todos.push(Todo {
-                        file: file_path.into(),
-                        line_number: lnum as i32,
-                        date: None,
-                        description: format!("{} is not a valid date.", date_string),
-                        state: TodoState::Malformed,
+            let (segment, comment) = match todo_segment {
+                Some(todo_segment) => todo_segment,
+                None => {
+                    // Second pass for annotations that were likely meant to be a 'todo'
+                    // Nothing is reported if the match is not inside of a comment, i.e. in a string literal
+                    let near_miss = segments.iter().find_map(|(segment, _)| {
+                        near_miss::diagnose(
+                            &near_miss_capture_matcher,
+                            &line[segment.clone()],
+                            &options.date_formats,
+                        )
                     });
-                    malformed_todo_count += 1;
+
+                    if let Some(near_miss) = near_miss {
+                        todos.push(malformed_todo(
+                            file_path,
+                            lnum,
+                            near_miss.reason,
+                            near_miss.suggestion,
+                            severity_policy.malformed,
+                        ));
+                        malformed_todo_count += 1;
+                    }
                     return Ok(true);
                 }
             };
 
-            // Validate expired
-            let todo_expired = match Utc::now().with_timezone(fixed_offset).date().cmp(&date) {
-                Ordering::Greater => {
-                    overdue_todo_count += 1;
-                    true
+            // Unwraps here are ok - as we've already verified 3 capture groups
+            let todo_start = segment.start + captures.get(0).unwrap().start();
+            let date_string = &line[segment.clone()][captures.get(1).unwrap()];
+            let description_string = line[segment.clone()][captures.get(2).unwrap()].trim();
+            let (date_string, severity) = Severity::split(date_string);
+            let malformed_severity = severity.unwrap_or(severity_policy.malformed);
+
+            // Conditions are checked against the project instead of a date
+            let condition = match Condition::check(date_string, project) {
+                Some(Ok(condition)) => Some(condition),
+                Some(Err(err)) => {
+                    todos.push(malformed_todo(
+                        file_path,
+                        lnum,
+                        format!("`{}` could not be checked: {}.", date_string, err),
+                        None,
+                        malformed_severity,
+                    ));
+                    malformed_todo_count += 1;
+                    return Ok(true);
                 }
-                _ => {
-                    valid_todo_count += 1;
-                    false
+                None => None,
+            };
+
+            // Validate date - relative deadlines are anchored to the date the line was committed
+            let deadline = match RelativeOffset::parse(date_string) {
+                _ if condition.is_some() => None,
+                Some(offset) => {
+                    let anchor = commit_dates
+                        .get_or_insert_with(|| {
+                            git::commit_dates(file_path, revision, contents, &options.fixed_offset)
+                        })
+                        .get(lnum as usize - 1)
+                        .copied()
+                        .flatten()
+                        // Uncommitted lines are anchored to today
+                        .unwrap_or_else(|| now.date());
+                    offset.after(anchor).map(|due| Deadline::Relative {
+                        offset,
+                        anchor,
+                        due,
+                    })
                 }
+                None => Deadline::parse(date_string, &options.date_formats),
             };
+            if condition.is_none() && deadline.is_none() {
+                let near_miss = near_miss::diagnose(
+                    &near_miss_capture_matcher,
+                    &line[segment.clone()],
+                    &options.date_formats,
+                );
+                todos.push(match near_miss {
+                    Some(near_miss) => malformed_todo(
+                        file_path,
+                        lnum,
+                        near_miss.reason,
+                        near_miss.suggestion,
+                        malformed_severity,
+                    ),
+                    None => malformed_todo(
+                        file_path,
+                        lnum,
+                        format!("{} is not a valid date.", date_string),
+                        None,
+                        malformed_severity,
+                    ),
+                });
+                malformed_todo_count += 1;
+                return Ok(true);
+            }
 
+            // Validate expired
+            let is_overdue = match (&condition, &deadline) {
+                (Some(condition), _) => condition.met,
+                (None, Some(deadline)) => deadline.is_overdue(now),
+                (None, None) => false,
+            };
+            let todo_expired = if is_overdue {
+                overdue_todo_count += 1;
+                true
+            } else {
+                valid_todo_count += 1;
+                false
+            };
+
+            // Overdue TODOs escalate by how long they have been overdue, unless they have a severity of their own
+            let severity = match &deadline {
+                _ if !todo_expired => None,
+                _ if severity.is_some() => severity,
+                Some(deadline) => Some(
+                    severity_policy.overdue(
+                        now.date()
+                            .signed_duration_since(deadline.last_day())
+                            .num_days(),
+                    ),
+                ),
+                None => Some(severity_policy.overdue(0)),
+            };
+
+            let (description, end_line_number) = continue_description(
+                description_string,
+                &lines,
+                lnum as usize,
+                todo_start,
+                comment,
+                comments.as_deref(),
+            );
+
+            let (tags, owner) = tags_and_owner(&description);
             todos.push(Todo {
                 file: file_path.into(),
                 line_number: lnum as i32,
-                date: Some(date.naive_local()),
-                description: description_string.to_string(),
+                end_line_number: end_line_number as i32,
+                deadline,
+                condition,
+                description,
+                tags,
+                owner,
                 state: {
                     if todo_expired {
                         TodoState::Overdue
@@ -185,6 +789,12 @@ fn search_todos(file_path: &Path, fixed_offset: &FixedOffset) -> Result<SearchRe
                         TodoState::Valid
                     }
                 },
+                suggestion: None,
+                severity,
+                rule: None,
+                suppression: None,
+                repository: None,
+                package: None,
             });
 
             // `true` signals to the search sink to continue searching
@@ -192,14 +802,180 @@ fn search_todos(file_path: &Path, fixed_offset: &FixedOffset) -> Result<SearchRe
         }),
     )?;
 
+    // Suppressed TODOs are taken out of the statistics
+    let mut suppressed_todos = vec![];
+    let mut suppressed_todo_count = 0;
+    let mut unsuppressed_todos = vec![];
+    for mut todo in todos {
+        todo.suppression = suppression::apply(&mut suppressions, todo.line_number);
+        if todo.suppression.is_none() {
+            unsuppressed_todos.push(todo);
+            continue;
+        }
+
+        match todo.state {
+            TodoState::Valid => valid_todo_count -= 1,
+            TodoState::Overdue => overdue_todo_count -= 1,
+            TodoState::Malformed => malformed_todo_count -= 1,
+        }
+        suppressed_todo_count += 1;
+        suppressed_todos.push(todo);
+    }
+
     Ok(SearchResult {
-        todos,
+        todos: unsuppressed_todos,
+        suppressed_todos,
+        suppressions,
         statistics: TodoStatistics {
             // fn search_todos() only runs for one file - hence we hard-code to one
             files_searched: 1,
             valid_todo_count,
             overdue_todo_count,
             malformed_todo_count,
+            suppressed_todo_count,
         },
+        packages: vec![],
     })
 }
+
+fn malformed_todo(
+    file_path: &Path,
+    line_number: u64,
+    description: String,
+    suggestion: Option<String>,
+    severity: Severity,
+) -> Todo {
+    Todo {
+        file: file_path.into(),
+        line_number: line_number as i32,
+        end_line_number: line_number as i32,
+        deadline: None,
+        condition: None,
+        description,
+        tags: vec![],
+        owner: None,
+        state: TodoState::Malformed,
+        suggestion,
+        severity: Some(severity),
+        rule: None,
+        suppression: None,
+        repository: None,
+        package: None,
+    }
+}
+
+/// Tags (i.e. `#perf`) and the owner (the first `@name`) mentioned in a TODO's description
+///
+/// Issue references like `#123` are not tags.
+fn tags_and_owner(description: &str) -> (Vec<String>, Option<String>) {
+    let mut tags: Vec<String> = vec![];
+    let mut owner = None;
+
+    for word in description.split_whitespace() {
+        let word = word.trim_end_matches(|c: char| ".,;:!?)".contains(c));
+        if let Some(tag) = word.strip_prefix('#') {
+            if tag.starts_with(char::is_alphabetic) && !tags.iter().any(|known| known == tag) {
+                tags.push(tag.to_string());
+            }
+        } else if let Some(name) = word.strip_prefix('@') {
+            if owner.is_none() && !name.is_empty() {
+                owner = Some(name.to_string());
+            }
+        }
+    }
+
+    (tags, owner)
+}
+
+/// Appends the following comment lines that continue a TODO's description
+///
+/// A description continues on lines starting with the same comment prefix at the same indentation,
+/// or on the following lines of a block comment - until a blank comment line or another TODO.
+/// Returns the full description and the line number it ends on.
+fn continue_description(
+    description: &str,
+    lines: &[&str],
+    line_number: usize,
+    todo_start: usize,
+    todo_comment: Option<&Comment>,
+    comments: Option<&[Vec<Comment>]>,
+) -> (String, usize) {
+    let mut description = description.to_string();
+    let mut end_line_number = line_number;
+
+    let mut append = |content: &str| {
+        if content.is_empty() || content.to_lowercase().contains("@todo") {
+            return false;
+        }
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(content);
+        true
+    };
+
+    // Block comments continue until they are closed
+    if let Some(comment) = todo_comment.filter(|comment| comment.line_marker.is_none()) {
+        let mut unterminated = comment.unterminated;
+        while unterminated && end_line_number < lines.len() {
+            // Line numbers are 1-based, so this is the index of the following line
+            let next = match comments.and_then(|comments| comments[end_line_number].first()) {
+                Some(next) if next.continued => next,
+                _ => break,
+            };
+
+            let content = lines[end_line_number][next.range.clone()]
+                .trim_start()
+                .trim_start_matches('*')
+                .trim();
+            if !append(content) {
+                break;
+            }
+            unterminated = next.unterminated;
+            end_line_number += 1;
+        }
+
+        return (description, end_line_number);
+    }
+
+    // Otherwise the comment prefix of the TODO's line has to be repeated, i.e. `    // `
+    let todo_line = lines[line_number - 1];
+    let prefix_start = match todo_comment {
+        Some(comment) => comment.range.start,
+        None => {
+            let indentation = todo_line.len() - todo_line.trim_start().len();
+            // Without comment information only a line that starts with the TODO can be continued
+            if todo_line[indentation..todo_start]
+                .chars()
+                .any(char::is_alphanumeric)
+            {
+                return (description, end_line_number);
+            }
+            indentation
+        }
+    };
+    let prefix = todo_line[prefix_start..todo_start].trim_end();
+    if prefix.is_empty() {
+        return (description, end_line_number);
+    }
+
+    while end_line_number < lines.len() {
+        let next_line = lines[end_line_number];
+        let is_comment = match comments {
+            Some(comments) => comments[end_line_number]
+                .iter()
+                .any(|comment| comment.range.start == prefix_start),
+            None => true,
+        };
+        let is_continued = is_comment
+            && matches!(next_line.get(..prefix_start), Some(indentation) if indentation.trim().is_empty())
+            && next_line[prefix_start..].starts_with(prefix);
+
+        if !is_continued || !append(next_line[prefix_start + prefix.len()..].trim()) {
+            break;
+        }
+        end_line_number += 1;
+    }
+
+    (description, end_line_number)
+}
@@ -0,0 +1,231 @@
+use std::fmt;
+
+use chrono::{Duration, Months, NaiveDate, NaiveDateTime, Weekday};
+
+/// When a TODO is due - coarse deadlines are due at the end of their period
+///
+/// Day: `2024-08-10`
+/// Month: `2024-08`
+/// Quarter: `2024-Q3`
+/// Week: `2024-W32` (ISO week)
+/// DateTime: `2024-08-10T17:00`
+/// Relative: `+90d` or `P3M` after the date the TODO was committed (the anchor)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Deadline {
+    Day(NaiveDate),
+    Month {
+        year: i32,
+        month: u32,
+    },
+    Quarter {
+        year: i32,
+        quarter: u32,
+    },
+    Week {
+        year: i32,
+        week: u32,
+    },
+    DateTime(NaiveDateTime),
+    Relative {
+        offset: RelativeOffset,
+        anchor: NaiveDate,
+        due: NaiveDate,
+    },
+}
+
+/// Time after which a TODO is due, relative to when it was committed
+///
+/// Either `+<n><d|w|m|y>` (i.e. `+90d`, `+6w`) or an ISO 8601 duration (i.e. `P3M`, `P1Y6M`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelativeOffset {
+    text: String,
+    months: u32,
+    days: u32,
+}
+
+impl RelativeOffset {
+    pub fn parse(text: &str) -> Option<RelativeOffset> {
+        let mut months = 0;
+        let mut days = 0;
+
+        let mut add = |amount: u32, unit: char| {
+            match unit.to_ascii_lowercase() {
+                'y' => months += amount.checked_mul(12)?,
+                'm' => months += amount,
+                'w' => days += amount.checked_mul(7)?,
+                'd' => days += amount,
+                _ => return None,
+            }
+            Some(())
+        };
+
+        if let Some(rest) = text.strip_prefix('+') {
+            let unit = rest.chars().last()?;
+            let amount = rest[..rest.len() - unit.len_utf8()].parse().ok()?;
+            add(amount, unit)?;
+        } else if let Some(mut rest) = text.strip_prefix('P') {
+            if rest.is_empty() {
+                return None;
+            }
+            while !rest.is_empty() {
+                let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+                let unit = rest[digits..].chars().next()?;
+                // Only upper-case designators are valid in ISO 8601, time components are not supported
+                if !unit.is_ascii_uppercase() {
+                    return None;
+                }
+                add(rest[..digits].parse().ok()?, unit)?;
+                rest = &rest[digits + 1..];
+            }
+        } else {
+            return None;
+        }
+
+        Some(RelativeOffset {
+            text: text.to_string(),
+            months,
+            days,
+        })
+    }
+
+    /// The date this offset after `anchor`
+    pub fn after(&self, anchor: NaiveDate) -> Option<NaiveDate> {
+        anchor
+            .checked_add_months(Months::new(self.months))?
+            .checked_add_signed(Duration::days(self.days.into()))
+    }
+}
+
+impl fmt::Display for RelativeOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Deadline {
+    /// Parses one of the built-in deadline formats, or else one of the custom `strftime` formats
+    pub fn parse(text: &str, custom_formats: &[String]) -> Option<Deadline> {
+        Deadline::parse_builtin(text).or_else(|| {
+            custom_formats.iter().find_map(|format| {
+                NaiveDateTime::parse_from_str(text, format)
+                    .map(Deadline::DateTime)
+                    .or_else(|_| NaiveDate::parse_from_str(text, format).map(Deadline::Day))
+                    .ok()
+            })
+        })
+    }
+
+    fn parse_builtin(text: &str) -> Option<Deadline> {
+        let is_digits = |part: &str, length: usize| {
+            part.len() == length && part.bytes().all(|b| b.is_ascii_digit())
+        };
+
+        let (year, rest) = text.split_once('-')?;
+        if !is_digits(year, 4) {
+            return None;
+        }
+        let year: i32 = year.parse().ok()?;
+
+        if let Some(quarter) = rest.strip_prefix('Q') {
+            let quarter: u32 = quarter.parse().ok().filter(|_| is_digits(quarter, 1))?;
+            return (1..=4)
+                .contains(&quarter)
+                .then_some(Deadline::Quarter { year, quarter });
+        }
+
+        if let Some(week) = rest.strip_prefix('W') {
+            let week: u32 = week.parse().ok().filter(|_| is_digits(week, 2))?;
+            // Validates the week exists in that year
+            NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+            return Some(Deadline::Week { year, week });
+        }
+
+        if is_digits(rest, 2) {
+            let month: u32 = rest.parse().ok()?;
+            NaiveDate::from_ymd_opt(year, month, 1)?;
+            return Some(Deadline::Month { year, month });
+        }
+
+        // Zero-padded only, as chrono would also accept i.e. `2024-8-1`
+        let matches_shape = |shape: &str| {
+            text.len() == shape.len()
+                && text.bytes().zip(shape.bytes()).all(|(b, expected)| {
+                    if expected == b'0' {
+                        b.is_ascii_digit()
+                    } else {
+                        b == expected
+                    }
+                })
+        };
+
+        if matches_shape("0000-00-00") {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .map(Deadline::Day)
+        } else if matches_shape("0000-00-00T00:00") {
+            NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M")
+                .ok()
+                .map(Deadline::DateTime)
+        } else {
+            None
+        }
+    }
+
+    /// The last day on which the TODO is still not overdue
+    pub fn last_day(&self) -> NaiveDate {
+        match *self {
+            Deadline::Day(date) => date,
+            Deadline::Month { year, month } => last_day_of_month(year, month),
+            Deadline::Quarter { year, quarter } => last_day_of_month(year, quarter * 3),
+            Deadline::Week { year, week } => NaiveDate::from_isoywd(year, week, Weekday::Sun),
+            Deadline::DateTime(date_time) => date_time.date(),
+            Deadline::Relative { due, .. } => due,
+        }
+    }
+
+    /// Whether the deadline has passed at the local date & time `now`
+    pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        match self {
+            Deadline::DateTime(date_time) => now > *date_time,
+            _ => now.date() > self.last_day(),
+        }
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> NaiveDate {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd(next_year, next_month, 1) - Duration::days(1)
+}
+
+impl fmt::Display for Deadline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Deadline::Day(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            Deadline::Month { year, month } => write!(f, "{:04}-{:02}", year, month),
+            Deadline::Quarter { year, quarter } => write!(f, "{:04}-Q{}", year, quarter),
+            Deadline::Week { year, week } => write!(f, "{:04}-W{:02}", year, week),
+            Deadline::DateTime(date_time) => write!(f, "{}", date_time.format("%Y-%m-%dT%H:%M")),
+            Deadline::Relative {
+                offset,
+                anchor,
+                due,
+            } => write!(
+                f,
+                "{} ({} after {})",
+                due.format("%Y-%m-%d"),
+                offset,
+                anchor.format("%Y-%m-%d")
+            ),
+        }
+    }
+}
+
+impl From<NaiveDate> for Deadline {
+    fn from(date: NaiveDate) -> Self {
+        Deadline::Day(date)
+    }
+}
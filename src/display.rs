@@ -1,12 +1,24 @@
+use std::path::PathBuf;
+
 use chrono::{FixedOffset, Utc};
+use serde_json::{json, Value};
 use termcolor::WriteColor;
 
 use crate::{
     cli::DisplayMode,
-    core::{SearchResult, Todo, TodoState},
+    core::{SearchResult, Todo, TodoState, TodoStatistics},
+    explain::Explanation,
+    rules::EffectiveRule,
+    severity::Severity,
+    suppression::Suppression,
 };
 
-fn print_single(output_target: &mut dyn WriteColor, todo: &Todo, fixed_offset: &FixedOffset) {
+fn print_single(
+    output_target: &mut dyn WriteColor,
+    todo: &Todo,
+    fixed_offset: &FixedOffset,
+    verbose: bool,
+) {
     let mut output_target = output_target;
     match todo.state {
         TodoState::Valid | TodoState::Overdue => {
@@ -28,35 +40,50 @@ fn print_single(output_target: &mut dyn WriteColor, todo: &Todo, fixed_offset: &
                 .expect("Could not write to output.");
             }
 
-            let date_str = &todo
-                .date
-                .expect("Date should be set if TODO is valid/overdue")
-                .format("%Y-%m-%d")
-                .to_string();
-
-            let day_difference = -Utc::now()
-                .with_timezone(fixed_offset)
-                .date()
-                .naive_local()
-                .signed_duration_since(
-                    todo.date
-                        .expect("Date should be set if TODO is valid/overdue"),
-                )
-                .num_days();
+            match (&todo.condition, &todo.deadline) {
+                (Some(condition), _) => {
+                    bunt::writeln!(
+                        output_target,
+                        "  {$magenta+dimmed}Due when:   {/$} {} (currently {})",
+                        condition.text,
+                        condition.current
+                    )
+                    .expect("Could not write to output.");
+                }
+                (None, Some(deadline)) => {
+                    let day_difference = -Utc::now()
+                        .with_timezone(fixed_offset)
+                        .date()
+                        .naive_local()
+                        .signed_duration_since(deadline.last_day())
+                        .num_days();
 
-            bunt::writeln!(
-                output_target,
-                "  {$magenta+dimmed}Due:        {/$} {} ({} days)",
-                date_str,
-                day_difference
-            )
-            .expect("Could not write to output.");
+                    bunt::writeln!(
+                        output_target,
+                        "  {$magenta+dimmed}Due:        {/$} {} ({} days)",
+                        deadline,
+                        day_difference
+                    )
+                    .expect("Could not write to output.");
+                }
+                (None, None) => {
+                    panic!("Deadline or condition should be set if TODO is valid/overdue")
+                }
+            }
             bunt::writeln!(
                 output_target,
                 "  {$blue+dimmed}Description:{/$} {}",
                 &todo.description
             )
             .expect("Could not write to output.");
+            if let Some(severity) = todo.severity {
+                bunt::writeln!(
+                    output_target,
+                    "  {$red+dimmed}Severity:   {/$} {}",
+                    severity
+                )
+                .expect("Could not write to output.");
+            }
         }
         TodoState::Malformed => {
             bunt::writeln!(
@@ -72,9 +99,42 @@ fn print_single(output_target: &mut dyn WriteColor, todo: &Todo, fixed_offset: &
                 &todo.description
             )
             .expect("Could not write to output.");
+            if let Some(suggestion) = &todo.suggestion {
+                bunt::writeln!(
+                    output_target,
+                    "  {$yellow+dimmed}Suggestion: {/$} {}",
+                    suggestion
+                )
+                .expect("Could not write to output.");
+            }
+            if let Some(severity) = todo.severity {
+                bunt::writeln!(
+                    output_target,
+                    "  {$yellow+dimmed}Severity:   {/$} {}",
+                    severity
+                )
+                .expect("Could not write to output.");
+            }
         }
     }
 
+    if verbose {
+        let rule = match &todo.rule {
+            Some(rule) => rule.to_string(),
+            None => "default".to_string(),
+        };
+        bunt::writeln!(output_target, "  {$cyan+dimmed}Rule:       {/$} {}", rule)
+            .expect("Could not write to output.");
+    }
+    if let Some(suppression) = &todo.suppression {
+        bunt::writeln!(
+            output_target,
+            "  {$cyan+dimmed}Suppressed: {/$} by {}",
+            suppression
+        )
+        .expect("Could not write to output.");
+    }
+
     bunt::writeln!(output_target).expect("Could not write to output.");
 }
 
@@ -87,12 +147,56 @@ pub fn print(
     let mut output_target = output_target;
     // Individual TODO details
     if !matches!(mode, DisplayMode::Concise) {
+        let mut package = None;
+        let mut repository = None;
         results.todos.iter().for_each(|todo| {
             if !matches!(mode, DisplayMode::OverdueOnly) || matches!(todo.state, TodoState::Overdue)
             {
-                print_single(&mut output_target, todo, fixed_offset)
+                // TODOs are grouped by the package, then the nested repository they are in
+                match &todo.package {
+                    Some(todo_package) if Some(todo_package) != package => {
+                        bunt::writeln!(
+                            output_target,
+                            "{$bold+magenta}Package {}:{/$}",
+                            todo_package.display()
+                        )
+                        .expect("Could not write to output.");
+                        bunt::writeln!(output_target).expect("Could not write to output.");
+                        repository = None;
+                    }
+                    _ => (),
+                }
+                package = todo.package.as_ref();
+
+                match &todo.repository {
+                    Some(todo_repository) if Some(todo_repository) != repository => {
+                        bunt::writeln!(
+                            output_target,
+                            "{$bold+cyan}Repository {}:{/$}",
+                            todo_repository.display()
+                        )
+                        .expect("Could not write to output.");
+                        bunt::writeln!(output_target).expect("Could not write to output.");
+                    }
+                    _ => (),
+                }
+                repository = todo.repository.as_ref();
+
+                print_single(
+                    &mut output_target,
+                    todo,
+                    fixed_offset,
+                    matches!(mode, DisplayMode::Verbose),
+                )
             }
-        })
+        });
+
+        // Suppressed TODOs are only shown when verbose
+        if matches!(mode, DisplayMode::Verbose) {
+            for todo in &results.suppressed_todos {
+                print_single(&mut output_target, todo, fixed_offset, true);
+            }
+        }
     }
 
     // Total stats
@@ -110,13 +214,295 @@ pub fn print(
     .expect("Could not write to output.");
 
     if results.statistics.overdue_todo_count > 0 {
-        bunt::writeln!(
+        bunt::write!(
             output_target,
             "{$bold+red} of which {} is/are overdue{/$}",
             results.statistics.overdue_todo_count,
         )
         .expect("Could not write to output.");
+    }
+    if results.statistics.suppressed_todo_count > 0 {
+        bunt::write!(
+            output_target,
+            " ({} suppressed)",
+            results.statistics.suppressed_todo_count,
+        )
+        .expect("Could not write to output.");
+    }
+    bunt::writeln!(output_target).expect("Could not write to output.");
+
+    // Overdue & malformed TODOs by severity
+    let severity_counts: Vec<String> = [
+        (Severity::Error, "error(s)"),
+        (Severity::Warning, "warning(s)"),
+        (Severity::Info, "info"),
+    ]
+    .iter()
+    .filter_map(|(severity, label)| {
+        let count = results
+            .todos
+            .iter()
+            .filter(|todo| todo.severity == Some(*severity))
+            .count();
+        (count > 0).then(|| format!("{} {}", count, label))
+    })
+    .collect();
+    if !severity_counts.is_empty() {
+        bunt::writeln!(output_target, "{$bold}{}{/$}", severity_counts.join(", "))
+            .expect("Could not write to output.");
+    }
+
+    print_package_counts(&mut output_target, results);
+    print_repository_counts(&mut output_target, results);
+}
+
+/// Prints the statistics of each package, if several root directories or workspace packages were searched
+fn print_package_counts(output_target: &mut dyn WriteColor, results: &SearchResult) {
+    let mut output_target = output_target;
+    for package in &results.packages {
+        bunt::writeln!(
+            output_target,
+            "  {$magenta}{}{/$}: {} file(s), {} todo(s), {} overdue",
+            package.root_directory.display(),
+            package.statistics.files_searched,
+            package.statistics.valid_todo_count + package.statistics.overdue_todo_count,
+            package.statistics.overdue_todo_count
+        )
+        .expect("Could not write to output.");
+    }
+}
+
+/// Prints the numbers of todos by repository, if any are in nested repositories
+fn print_repository_counts(output_target: &mut dyn WriteColor, results: &SearchResult) {
+    let mut output_target = output_target;
+    if results.todos.iter().all(|todo| todo.repository.is_none()) {
+        return;
+    }
+
+    let mut repositories: Vec<Option<&PathBuf>> = results
+        .todos
+        .iter()
+        .map(|todo| todo.repository.as_ref())
+        .collect();
+    repositories.sort();
+    repositories.dedup();
+
+    for repository in repositories {
+        let todos = results
+            .todos
+            .iter()
+            .filter(|todo| todo.repository.as_ref() == repository);
+        let (todo_count, overdue_count) =
+            todos.fold((0, 0), |(todos, overdue), todo| match todo.state {
+                TodoState::Valid => (todos + 1, overdue),
+                TodoState::Overdue => (todos + 1, overdue + 1),
+                TodoState::Malformed => (todos, overdue),
+            });
+
+        let name = match repository {
+            Some(repository) => repository.display().to_string(),
+            None => "root directory".to_string(),
+        };
+        bunt::writeln!(
+            output_target,
+            "  {$cyan}{}{/$}: {} todo(s), {} overdue",
+            name,
+            todo_count,
+            overdue_count
+        )
+        .expect("Could not write to output.");
+    }
+}
+
+/// Prints the results of a search, the inline suppressions & the policy violations as a single JSON document
+pub fn print_json(
+    output_target: &mut dyn WriteColor,
+    results: &SearchResult,
+    violations: &[String],
+) {
+    let statistics = |statistics: &TodoStatistics| {
+        json!({
+            "files_searched": statistics.files_searched,
+            "valid_todo_count": statistics.valid_todo_count,
+            "overdue_todo_count": statistics.overdue_todo_count,
+            "malformed_todo_count": statistics.malformed_todo_count,
+            "suppressed_todo_count": statistics.suppressed_todo_count,
+        })
+    };
+
+    let document = json!({
+        "todos": results.todos.iter().map(todo_json).collect::<Vec<Value>>(),
+        "suppressed_todos": results.suppressed_todos.iter().map(todo_json).collect::<Vec<Value>>(),
+        "statistics": statistics(&results.statistics),
+        "packages": results
+            .packages
+            .iter()
+            .map(|package| {
+                json!({
+                    "root_directory": package.root_directory,
+                    "statistics": statistics(&package.statistics),
+                })
+            })
+            .collect::<Vec<Value>>(),
+        "suppressions": results
+            .suppressions
+            .iter()
+            .map(|suppression| {
+                json!({
+                    "file": suppression.file,
+                    "line_number": suppression.line_number,
+                    "directive": suppression.directive.to_string(),
+                    "reason": suppression.reason,
+                    "used": suppression.used,
+                    "missing_reason": suppression.missing_reason,
+                })
+            })
+            .collect::<Vec<Value>>(),
+        "policy_violations": violations,
+    });
+
+    writeln!(
+        output_target,
+        "{}",
+        serde_json::to_string_pretty(&document).expect("JSON should be serializable")
+    )
+    .expect("Could not write to output.");
+}
+
+fn todo_json(todo: &Todo) -> Value {
+    let state = match todo.state {
+        TodoState::Valid => "valid",
+        TodoState::Overdue => "overdue",
+        TodoState::Malformed => "malformed",
+    };
+
+    json!({
+        "file": todo.file,
+        "line_number": todo.line_number,
+        "end_line_number": todo.end_line_number,
+        "state": state,
+        "deadline": todo.deadline.as_ref().map(ToString::to_string),
+        "condition": todo.condition.as_ref().map(|condition| {
+            json!({ "text": condition.text, "current": condition.current, "met": condition.met })
+        }),
+        "description": todo.description,
+        "tags": todo.tags,
+        "owner": todo.owner,
+        "severity": todo.severity.map(|severity| severity.to_string()),
+        "suggestion": todo.suggestion,
+        "rule": todo.rule.as_ref().map(rule_json),
+        "suppression": todo.suppression.as_ref().map(ToString::to_string),
+        "repository": todo.repository,
+        "package": todo.package,
+    })
+}
+
+/// The effective rule of a TODO - settings no rule sets are `null`
+fn rule_json(rule: &EffectiveRule) -> Value {
+    json!({
+        "paths": rule.paths,
+        "severity": rule.severity.as_ref().map(|severity| {
+            json!({
+                "overdue": severity.overdue.to_string(),
+                "malformed": severity.malformed.to_string(),
+                "escalation": severity
+                    .escalation
+                    .iter()
+                    .map(|escalation| {
+                        json!({
+                            "after_days": escalation.after_days,
+                            "severity": escalation.severity.to_string(),
+                        })
+                    })
+                    .collect::<Vec<Value>>(),
+            })
+        }),
+        "due_soon": rule.due_soon,
+        "fail": rule.fail,
+        "allowed_tags": rule.allowed_tags,
+        "require_owner": rule.require_owner,
+    })
+}
+
+/// Prints the inline suppressions that are unused or missing a required reason, if any
+pub fn print_suppressions(output_target: &mut dyn WriteColor, suppressions: &[Suppression]) {
+    let mut output_target = output_target;
+    for suppression in suppressions {
+        if suppression.missing_reason {
+            bunt::writeln!(
+                output_target,
+                "{$yellow}Suppression is missing a reason:{/$} {}",
+                suppression
+            )
+            .expect("Could not write to output.");
+        } else if !suppression.used {
+            bunt::writeln!(
+                output_target,
+                "{$yellow}Unused suppression:{/$} {}",
+                suppression
+            )
+            .expect("Could not write to output.");
+        }
+    }
+}
+
+/// Prints the violations of the budget & ratchet policies, if any
+pub fn print_policy_violations(output_target: &mut dyn WriteColor, violations: &[String]) {
+    let mut output_target = output_target;
+    if violations.is_empty() {
+        return;
+    }
+
+    bunt::writeln!(output_target, "{$bold+red}Policy violations:{/$}")
+        .expect("Could not write to output.");
+    for violation in violations {
+        bunt::writeln!(output_target, "  {$red}-{/$} {}", violation)
+            .expect("Could not write to output.");
+    }
+}
+
+/// Prints the files that would be searched, one per line
+///
+/// The number of files goes to `summary_target`, so the output stays one path per line for other tools.
+pub fn print_files(
+    output_target: &mut dyn WriteColor,
+    summary_target: &mut dyn WriteColor,
+    files: &[PathBuf],
+) {
+    let mut output_target = output_target;
+    for file in files {
+        bunt::writeln!(output_target, "{}", file.display()).expect("Could not write to output.");
+    }
+
+    let mut summary_target = summary_target;
+    bunt::writeln!(
+        summary_target,
+        "{$green+intense}{} file(s) would be searched{/$}",
+        files.len()
+    )
+    .expect("Could not write to output.");
+}
+
+/// Prints why a file is or isn't searched
+pub fn print_explanation(output_target: &mut dyn WriteColor, explanation: &Explanation) {
+    let mut output_target = output_target;
+    if explanation.searched {
+        bunt::writeln!(
+            output_target,
+            "{$bold+green}{$underline}{}{/$} is searched:{/$}",
+            explanation.path.display()
+        )
+        .expect("Could not write to output.");
     } else {
-        bunt::writeln!(output_target).expect("Could not write to output.");
+        bunt::writeln!(
+            output_target,
+            "{$bold+yellow}{$underline}{}{/$} is not searched:{/$}",
+            explanation.path.display()
+        )
+        .expect("Could not write to output.");
+    }
+
+    for reason in &explanation.reasons {
+        bunt::writeln!(output_target, "  - {}", reason).expect("Could not write to output.");
     }
 }
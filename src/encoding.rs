@@ -0,0 +1,89 @@
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, WINDOWS_1252};
+use eyre::{eyre, Result};
+
+/// How file contents are decoded before being searched for TODOs
+///
+/// Auto: sniff a BOM, then try UTF-8, UTF-16 (without BOM) and finally Latin-1/Windows-1252
+/// Lossy: sniff a BOM, then decode as UTF-8 replacing invalid sequences
+/// Forced: always decode with the given encoding (a BOM still takes precedence)
+#[derive(Debug, Clone, Copy)]
+pub enum EncodingMode {
+    Auto,
+    Lossy,
+    Forced(&'static Encoding),
+}
+
+impl EncodingMode {
+    /// Parses `auto`, `lossy` or any WHATWG encoding label (i.e. `utf-16le`, `latin1`, `shift_jis`)
+    pub fn from_label(label: &str) -> Result<EncodingMode> {
+        match label.to_ascii_lowercase().as_str() {
+            "auto" => Ok(EncodingMode::Auto),
+            "lossy" => Ok(EncodingMode::Lossy),
+            other => Encoding::for_label(other.as_bytes())
+                .map(EncodingMode::Forced)
+                .ok_or_else(|| eyre!("{} is not a known encoding.", label)),
+        }
+    }
+}
+
+/// Decodes raw file contents into UTF-8 text
+///
+/// Line terminators are preserved by transcoding, so line numbers stay the same.
+/// Returns `None` if the contents look like a binary file and should not be searched.
+pub fn decode(bytes: &[u8], mode: EncodingMode) -> Option<String> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+        return Some(text.into_owned());
+    }
+
+    match mode {
+        EncodingMode::Forced(encoding) => {
+            let (text, _) = encoding.decode_without_bom_handling(bytes);
+            Some(text.into_owned())
+        }
+        EncodingMode::Lossy => Some(String::from_utf8_lossy(bytes).into_owned()),
+        EncodingMode::Auto => {
+            // NUL bytes are valid UTF-8 but hint at either UTF-16 without a BOM or a binary file
+            if bytes.contains(&0) {
+                return sniff_utf16(bytes).map(|encoding| {
+                    let (text, _) = encoding.decode_without_bom_handling(bytes);
+                    text.into_owned()
+                });
+            }
+
+            if let Ok(text) = std::str::from_utf8(bytes) {
+                return Some(text.to_string());
+            }
+
+            // Every byte is valid Windows-1252 - a superset of Latin-1 commonly used by legacy sources
+            let (text, _) = WINDOWS_1252.decode_without_bom_handling(bytes);
+            Some(text.into_owned())
+        }
+    }
+}
+
+/// Guesses UTF-16 without a BOM from the position of NUL bytes in mostly-ASCII text
+fn sniff_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.len() < 2 || bytes.len() % 2 == 1 {
+        return None;
+    }
+
+    let sample = &bytes[..bytes.len().min(4096)];
+    let pairs = sample.len() / 2;
+    let even_nuls = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_nuls = sample
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|b| **b == 0)
+        .count();
+
+    // ASCII characters encoded as UTF-16 have a NUL for every high byte
+    if odd_nuls * 10 >= pairs * 9 && even_nuls == 0 {
+        Some(UTF_16LE)
+    } else if even_nuls * 10 >= pairs * 9 && odd_nuls == 0 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
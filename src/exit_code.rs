@@ -0,0 +1,72 @@
+use chrono::NaiveDate;
+
+use crate::{
+    cli::FailOn,
+    core::{SearchResult, Todo, TodoState},
+    severity::Severity,
+};
+
+/// No TODOs fail the build
+pub const SUCCESS: i32 = 0;
+/// There are error-level overdue TODOs
+pub const OVERDUE: i32 = 1;
+/// `todo-ci` itself failed, i.e. because of invalid arguments or an unreadable file
+pub const ERROR: i32 = 2;
+/// There are error-level malformed TODOs
+pub const MALFORMED: i32 = 3;
+/// There are TODOs that are due soon
+pub const DUE_SOON: i32 = 4;
+/// The TODOs violate a policy of the project configuration
+pub const POLICY_VIOLATION: i32 = 5;
+
+/// Exit code for the TODOs that fail the build - the first of overdue, malformed and due soon TODOs,
+/// or else policy violations
+///
+/// Overdue & malformed TODOs only fail the build if they are error-level.
+pub fn from_results(
+    results: &SearchResult,
+    fail_on: &[FailOn],
+    due_soon_days: i64,
+    today: NaiveDate,
+    policy_violations: &[String],
+) -> i32 {
+    let fails = |todo: &Todo, kind: &FailOn| match kind {
+        // Path rules can keep TODOs from failing the build
+        _ if matches!(&todo.rule, Some(rule) if rule.fail == Some(false)) => false,
+        FailOn::Overdue => {
+            matches!(todo.state, TodoState::Overdue) && todo.severity == Some(Severity::Error)
+        }
+        FailOn::Malformed => {
+            matches!(todo.state, TodoState::Malformed) && todo.severity == Some(Severity::Error)
+        }
+        FailOn::DueSoon => match (&todo.state, &todo.deadline) {
+            (TodoState::Valid, Some(deadline)) => {
+                let due_soon_days = todo
+                    .rule
+                    .as_ref()
+                    .and_then(|rule| rule.due_soon)
+                    .unwrap_or(due_soon_days);
+                deadline.last_day().signed_duration_since(today).num_days() <= due_soon_days
+            }
+            _ => false,
+        },
+    };
+
+    [
+        (FailOn::Overdue, OVERDUE),
+        (FailOn::Malformed, MALFORMED),
+        (FailOn::DueSoon, DUE_SOON),
+    ]
+    .iter()
+    .find(|(kind, _)| fail_on.contains(kind) && results.todos.iter().any(|todo| fails(todo, kind)))
+    .map_or_else(
+        || {
+            if policy_violations.is_empty() {
+                SUCCESS
+            } else {
+                POLICY_VIOLATION
+            }
+        },
+        |(_, code)| *code,
+    )
+}
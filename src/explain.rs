@@ -0,0 +1,391 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Result, WrapErr};
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match,
+};
+
+use crate::{
+    archive::Format,
+    attributes::GitAttributes,
+    cli::NestedRepos,
+    core::{self, SearchOptions},
+    encoding, paths,
+    source::SourceKind,
+};
+
+/// Why a file is or isn't searched for TODOs
+#[derive(Debug)]
+pub struct Explanation {
+    pub path: PathBuf,
+    pub searched: bool,
+    /// The rules that include or exclude the file, in the order they are applied
+    pub reasons: Vec<String>,
+}
+
+/// Explains why `path` is or isn't searched for TODOs when searching `root_directory`
+///
+/// Whether the file is searched comes from the walker itself, the reasons from matching the file against
+/// the same ignore files, file types & globs again.
+pub fn explain(root_directory: &Path, path: &Path, options: &SearchOptions) -> Result<Explanation> {
+    // Only the working directory is walked with ignore files on disk
+    if !matches!(options.source, SourceKind::WorkingDirectory) {
+        return Err(eyre!(
+            "explain can't be used with --rev, --staged, --files-from or stdin"
+        ));
+    }
+    if root_directory.is_file() && Format::detect(root_directory).is_some() {
+        return Err(eyre!(
+            "explain can't be used with archives, {} is one",
+            root_directory.display()
+        ));
+    }
+
+    let canonical_root = root_directory
+        .canonicalize()
+        .wrap_err_with(|| format!("{} does not exist", root_directory.display()))?;
+    let canonical_path = path
+        .canonicalize()
+        .wrap_err_with(|| format!("{} does not exist", path.display()))?;
+    let relative_path = canonical_path
+        .strip_prefix(&canonical_root)
+        .map_err(|_| {
+            eyre!(
+                "{} is not inside of {}",
+                path.display(),
+                root_directory.display()
+            )
+        })?
+        .to_path_buf();
+
+    let explanation = |searched: bool, reasons: Vec<String>| Explanation {
+        path: path.into(),
+        searched,
+        reasons,
+    };
+
+    if canonical_path.is_dir() {
+        return Ok(explanation(
+            false,
+            vec!["it is a directory, only files are searched".to_string()],
+        ));
+    }
+
+    let mut walked = false;
+    let nested_repositories = core::walk_files_and(
+        |file| {
+            if file.path().canonicalize().ok().as_deref() == Some(canonical_path.as_path()) {
+                walked = true;
+            }
+            Ok(())
+        },
+        root_directory.into(),
+        options,
+    )?;
+
+    let nested_repository = nested_repositories.iter().find(|repository| {
+        matches!(repository.canonicalize(), Ok(repository) if canonical_path.starts_with(&repository))
+    });
+    if let Some(repository) = nested_repository {
+        if matches!(options.nested_repos, NestedRepos::Skip) {
+            return Ok(explanation(
+                false,
+                vec![format!(
+                    "it is in the nested repository {}, which is skipped",
+                    repository.display()
+                )],
+            ));
+        }
+
+        // Nested repositories are searched separately
+        let mut nested_explanation = explain(
+            repository,
+            path,
+            &options.for_nested_repository(repository)?,
+        )?;
+        nested_explanation.reasons.insert(
+            0,
+            format!(
+                "it is in the nested repository {}, which is searched separately",
+                repository.display()
+            ),
+        );
+        return Ok(nested_explanation);
+    }
+
+    let mut reasons = vec![];
+
+    if walked {
+        if let Some((glob, ignore_file)) =
+            ignore_match(root_directory, &canonical_root, &relative_path, options)
+                .filter(|(glob, _)| glob.starts_with('!'))
+        {
+            reasons.push(format!(
+                "it is whitelisted by `{}` in {}",
+                glob,
+                ignore_file.display()
+            ));
+        }
+        if !options.file_types.is_empty() {
+            reasons.push(format!(
+                "it is of the file types {}",
+                options.file_types.join(", ")
+            ));
+        }
+    } else {
+        reasons.push(walker_reason(
+            root_directory,
+            &canonical_root,
+            &relative_path,
+            options,
+        )?);
+        return Ok(explanation(false, reasons));
+    }
+
+    if !options.include.is_empty() {
+        let include = paths::glob_set(&options.include)?;
+        match include.matches(&relative_path).first() {
+            Some(index) => reasons.push(format!(
+                "it matches --include `{}`",
+                options.include[*index]
+            )),
+            None => {
+                reasons.push("it does not match any --include glob".to_string());
+                return Ok(explanation(false, reasons));
+            }
+        }
+    }
+
+    let exclude = paths::glob_set(&options.exclude)?;
+    if let Some(index) = exclude.matches(&relative_path).first() {
+        reasons.push(format!(
+            "it matches --exclude `{}`",
+            options.exclude[*index]
+        ));
+        return Ok(explanation(false, reasons));
+    }
+
+    if !options.no_ignore && !options.no_gitattributes {
+        if let Some((attribute, attributes_file)) =
+            GitAttributes::new(root_directory)?.skipped_by(&relative_path)
+        {
+            reasons.push(format!(
+                "it is marked `{}` in {} (use --no-gitattributes to search it)",
+                attribute,
+                attributes_file.display()
+            ));
+            return Ok(explanation(false, reasons));
+        }
+    }
+
+    let contents = std::fs::read(&canonical_path)
+        .wrap_err_with(|| format!("Could not read {}", path.display()))?;
+    if encoding::decode(&contents, options.encoding_mode).is_none() {
+        reasons.push("it is binary or can't be decoded as text".to_string());
+        return Ok(explanation(false, reasons));
+    }
+
+    if reasons.is_empty() {
+        reasons.push("it is not ignored".to_string());
+    }
+
+    Ok(explanation(true, reasons))
+}
+
+/// Why the walker skipped a file - checked in about the order the walker applies its filters
+fn walker_reason(
+    root_directory: &Path,
+    canonical_root: &Path,
+    relative_path: &Path,
+    options: &SearchOptions,
+) -> Result<String> {
+    let file_name = relative_path.file_name().unwrap_or_default();
+    if options
+        .custom_ignore_file_names()
+        .iter()
+        .any(|ignore_file_name| file_name == ignore_file_name.as_str())
+    {
+        return Ok("it is an ignore file".to_string());
+    }
+
+    if let Some(max_depth) = options.max_depth {
+        let depth = relative_path.iter().count();
+        if depth > max_depth {
+            return Ok(format!(
+                "it is at depth {}, deeper than --max-depth {}",
+                depth, max_depth
+            ));
+        }
+    }
+
+    // Whitelisted files are not hidden either
+    match ignore_match(root_directory, canonical_root, relative_path, options) {
+        Some((glob, _)) if glob.starts_with('!') => (),
+        Some((glob, ignore_file)) => {
+            return Ok(format!(
+                "it is ignored by `{}` in {}",
+                glob,
+                ignore_file.display()
+            ));
+        }
+        None => {
+            let hidden = relative_path
+                .iter()
+                .any(|component| component.to_string_lossy().starts_with('.'));
+            if hidden && !options.no_ignore && !options.hidden {
+                return Ok("it is hidden (use --hidden to search it)".to_string());
+            }
+        }
+    }
+
+    if let Some(types) = options.types()? {
+        if types.matched(relative_path, false).is_ignore() {
+            return Ok(format!(
+                "it is not of the file types {}",
+                options.file_types.join(", ")
+            ));
+        }
+    }
+
+    Ok("it is skipped while walking the root directory".to_string())
+}
+
+/// The matching line of the ignore files that ignores or whitelists a file, with the ignore file it is in
+///
+/// Like the walker, the directories on the way to the file are checked before the file itself.
+fn ignore_match(
+    root_directory: &Path,
+    canonical_root: &Path,
+    relative_path: &Path,
+    options: &SearchOptions,
+) -> Option<(String, PathBuf)> {
+    let mut entries: Vec<&Path> = relative_path
+        .ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .collect();
+    entries.reverse();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let is_dir = index + 1 < entries.len();
+        match entry_ignore_match(root_directory, canonical_root, entry, is_dir, options) {
+            // Whitelisted directories are still walked, but their files can be ignored
+            Some((glob, _)) if is_dir && glob.starts_with('!') => (),
+            Some(found) => return Some(found),
+            None => (),
+        }
+    }
+
+    None
+}
+
+/// Mirrors the walker's matching of a single entry: custom ignore files take precedence over `.ignore`,
+/// `.gitignore`, `.git/info/exclude` and the global gitignore file - and ignore files of deeper directories
+/// over those of their parents. Ignore files above the root directory match the entry's path as walked,
+/// joined onto the absolute root directory, so their anchored patterns rarely apply.
+fn entry_ignore_match(
+    root_directory: &Path,
+    canonical_root: &Path,
+    relative_path: &Path,
+    is_dir: bool,
+    options: &SearchOptions,
+) -> Option<(String, PathBuf)> {
+    let walked_path = root_directory.join(relative_path);
+    let walked_path = walked_path
+        .strip_prefix(".")
+        .unwrap_or(&walked_path)
+        .to_path_buf();
+
+    // Directories with their ignore files, deepest first, and the path they match the entry by
+    let mut directories: Vec<(PathBuf, PathBuf)> = relative_path
+        .ancestors()
+        .skip(1)
+        .map(|ancestor| (root_directory.join(ancestor), walked_path.clone()))
+        .collect();
+    if !options.no_ignore && !options.no_parent_ignore {
+        let absolute_path = canonical_root.join(&walked_path);
+        directories.extend(
+            canonical_root
+                .ancestors()
+                .skip(1)
+                .map(|parent| (parent.to_path_buf(), absolute_path.clone())),
+        );
+    }
+
+    let any_git = directories
+        .iter()
+        .any(|(directory, _)| directory.join(".git").exists());
+    // Only the ignore files up to the closest repository apply
+    let repository_directories = match directories
+        .iter()
+        .position(|(directory, _)| directory.join(".git").exists())
+    {
+        Some(index) => &directories[..=index],
+        None => &directories[..],
+    };
+
+    let mut ignore_files: Vec<(PathBuf, &Path, &Path)> = vec![];
+    for ignore_file_name in options.custom_ignore_file_names() {
+        for (directory, path) in &directories {
+            ignore_files.push((directory.join(&ignore_file_name), directory, path));
+        }
+    }
+    if !options.no_ignore {
+        for (directory, path) in &directories {
+            ignore_files.push((directory.join(".ignore"), directory, path));
+        }
+        if any_git && !options.no_gitignore {
+            for (directory, path) in repository_directories {
+                ignore_files.push((directory.join(".gitignore"), directory, path));
+            }
+            for (directory, path) in repository_directories {
+                ignore_files.push((directory.join(".git/info/exclude"), directory, path));
+            }
+        }
+    }
+
+    for (ignore_file, directory, path) in ignore_files.iter().filter(|(file, _, _)| file.is_file())
+    {
+        let mut builder = GitignoreBuilder::new(directory);
+        builder.add(ignore_file);
+        let gitignore = match builder.build() {
+            Ok(gitignore) => gitignore,
+            Err(_) => continue,
+        };
+
+        if let Some(found) = matched(&gitignore, path, is_dir, ignore_file) {
+            return Some(found);
+        }
+    }
+
+    if any_git && !options.no_ignore && !options.no_global_ignore {
+        let (gitignore, _) = Gitignore::global();
+        return matched(
+            &gitignore,
+            &walked_path,
+            is_dir,
+            Path::new("the global gitignore file"),
+        );
+    }
+
+    None
+}
+
+fn matched(
+    gitignore: &Gitignore,
+    path: &Path,
+    is_dir: bool,
+    ignore_file: &Path,
+) -> Option<(String, PathBuf)> {
+    // Absolute paths can only be matched by ignore files above them
+    if path.has_root() && !path.starts_with(gitignore.path()) {
+        return None;
+    }
+
+    match gitignore.matched(path, is_dir) {
+        Match::Ignore(glob) | Match::Whitelist(glob) => {
+            Some((glob.original().to_string(), ignore_file.to_path_buf()))
+        }
+        Match::None => None,
+    }
+}
@@ -0,0 +1,240 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use chrono::{FixedOffset, NaiveDate, TimeZone};
+use eyre::{eyre, Result, WrapErr};
+
+/// Dates on which each line of a file was committed, using `git blame`
+///
+/// Index `n` holds line `n + 1`, with `None` for lines that are not committed yet.
+/// Files outside of a git repository (or without git installed) have no commit dates at all.
+///
+/// The file is blamed as of `revision` if set, or with `contents` instead of the file in the working
+/// directory if set, i.e. its staged contents or those read from stdin.
+pub fn commit_dates(
+    file_path: &Path,
+    revision: Option<&str>,
+    contents: Option<&str>,
+    fixed_offset: &FixedOffset,
+) -> Vec<Option<NaiveDate>> {
+    let directory = match file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let file_name = match file_path.file_name() {
+        Some(file_name) => file_name,
+        None => return vec![],
+    };
+
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(directory)
+        .args(["blame", "--line-porcelain"])
+        .args(revision);
+    if contents.is_some() {
+        command.args(["--contents", "-"]).stdin(Stdio::piped());
+    }
+    let child = command
+        .arg("--")
+        .arg(file_name)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(_) => return vec![],
+    };
+
+    // Written from another thread, so git doesn't block on a full stdout while we block on its stdin
+    let writer = contents.map(|contents| {
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let contents = contents.to_string();
+        std::thread::spawn(move || stdin.write_all(contents.as_bytes()))
+    });
+    let output = child.wait_with_output();
+    if let Some(writer) = writer {
+        // git stops reading if the file isn't in a repository, which fails the blame anyway
+        let _ = writer.join();
+    }
+
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        _ => return vec![],
+    };
+
+    let mut dates = vec![];
+    let mut committed = false;
+    let mut date = None;
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line.starts_with('\t') {
+            // The line's content ends the line's blame information
+            dates.push(if committed { date } else { None });
+        } else if let Some(timestamp) = line.strip_prefix("author-time ") {
+            date = timestamp
+                .parse()
+                .ok()
+                .map(|timestamp| fixed_offset.timestamp(timestamp, 0).date().naive_local());
+        } else if line.len() > 40
+            && line.as_bytes()[40] == b' '
+            && line.as_bytes()[..40].iter().all(u8::is_ascii_hexdigit)
+        {
+            // Header of the form `<commit hash> <original line> <final line> ...`
+            committed = !line.as_bytes()[..40].iter().all(|b| *b == b'0');
+        }
+    }
+
+    dates
+}
+
+/// A file in a commit's tree or the index
+#[derive(Debug)]
+pub struct TreeEntry {
+    /// Path relative to the directory the tree was listed in
+    pub path: PathBuf,
+    pub object_id: String,
+}
+
+/// Lists the files of `revision` - or of the index if it's `None` - in `directory` and its subdirectories
+///
+/// Submodules and symbolic links are left out, as they have no contents to search.
+pub fn tree_entries(directory: &Path, revision: Option<&str>) -> Result<Vec<TreeEntry>> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(directory);
+    match revision {
+        Some(revision) => command.args(["ls-tree", "-r", "-z", revision]),
+        None => command.args(["ls-files", "--stage", "-z"]),
+    };
+
+    let output = command.output().wrap_err("Could not run git")?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "Could not list the files of {}: {}",
+            revision.unwrap_or("the index"),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let mut entries = vec![];
+    for entry in output.stdout.split(|byte| *byte == 0) {
+        // `<mode> <type> <object id>\t<path>` for trees, `<mode> <object id> <stage>\t<path>` for the index
+        let entry = String::from_utf8_lossy(entry);
+        let (info, path) = match entry.split_once('\t') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let info: Vec<&str> = info.split(' ').collect();
+        let (mode, object_id) = match (revision, info.as_slice()) {
+            (Some(_), [mode, "blob", object_id]) => (*mode, *object_id),
+            // Only the merged stage of conflicted files
+            (None, [mode, object_id, "0"]) => (*mode, *object_id),
+            _ => continue,
+        };
+        if mode == "120000" || mode == "160000" {
+            continue;
+        }
+
+        entries.push(TreeEntry {
+            path: path.into(),
+            object_id: object_id.to_string(),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Contents of a file of `revision` - or of the index if it's `None` - by its path relative to
+/// `directory`, which may lead out of it with `..`
+///
+/// `None` if the file is not in the tree or `directory` is not in a repository.
+pub fn show_file(directory: &Path, revision: Option<&str>, path: &Path) -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .arg("show")
+        .arg(tree_path(revision, path))
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(output.stdout)
+    } else {
+        None
+    }
+}
+
+/// Whether a file or directory exists in `revision` - or in the index if it's `None`, see `show_file`
+pub fn path_exists(directory: &Path, revision: Option<&str>, path: &Path) -> bool {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(directory);
+    match revision {
+        Some(_) => command
+            .args(["cat-file", "-e"])
+            .arg(tree_path(revision, path)),
+        // The index has no entries for directories, but lists the files in them
+        None => command
+            .args(["ls-files", "--error-unmatch", "--"])
+            .arg(path),
+    };
+
+    matches!(
+        command.stdout(Stdio::null()).stderr(Stdio::null()).status(),
+        Ok(status) if status.success()
+    )
+}
+
+/// `<revision>:./<path>`, or `:./<path>` for the index - `./` makes the path relative to the current
+/// directory instead of the root of the repository
+fn tree_path(revision: Option<&str>, path: &Path) -> String {
+    format!(
+        "{}:./{}",
+        revision.unwrap_or_default(),
+        path.to_string_lossy().replace('\\', "/")
+    )
+}
+
+/// Reads the contents of blobs from the repository of `directory`, in the order of `object_ids`
+pub fn read_blobs(directory: &Path, object_ids: &[String]) -> Result<Vec<Vec<u8>>> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .wrap_err("Could not run git")?;
+
+    // Written from another thread, so git doesn't block on a full stdout while we block on its stdin
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input: String = object_ids.iter().map(|id| format!("{}\n", id)).collect();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let mut blobs = vec![];
+    for object_id in object_ids {
+        // `<object id> <type> <size>`, followed by the contents and a newline
+        let mut header = String::new();
+        stdout.read_line(&mut header)?;
+        let size: usize = match header.trim_end().split(' ').collect::<Vec<_>>().as_slice() {
+            [_, _, size] => size.parse()?,
+            _ => return Err(eyre!("Could not read object {}", object_id)),
+        };
+
+        let mut contents = vec![0; size + 1];
+        stdout.read_exact(&mut contents)?;
+        contents.truncate(size);
+        blobs.push(contents);
+    }
+
+    writer
+        .join()
+        .expect("Writer should not panic")
+        .wrap_err("Could not write to git")?;
+    child.wait()?;
+
+    Ok(blobs)
+}
@@ -0,0 +1,174 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+
+/// Where the states of issues for `issue` conditions are read from, configured under `[issues]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct IssueSource {
+    /// JSON or CSV export of issues with `number` (or `iid`) and `state` fields, relative to the root directory
+    pub file: Option<PathBuf>,
+    /// GitHub/GitLab-shaped REST endpoint, where `{url}/{number}` responds with the issue's `state`
+    pub url: Option<String>,
+    /// Environment variable holding a token to authenticate to `url` with
+    pub token_env: Option<String>,
+    /// File to cache states fetched from `url` in, relative to the root directory - used when `url` can't be reached
+    pub cache_file: Option<PathBuf>,
+}
+
+/// Looks up issue states from an `IssueSource`, reading each issue at most once
+///
+/// States fetched from `url` are only written to the cache file by `write_cache`, once the search is done.
+#[derive(Debug)]
+pub struct IssueTracker {
+    root_directory: PathBuf,
+    source: IssueSource,
+    export: Option<Result<HashMap<u64, String>, String>>,
+    cache: Option<HashMap<u64, String>>,
+    /// States looked up from `url` during this run, including failed lookups
+    fetched: HashMap<u64, Result<String, String>>,
+}
+
+impl IssueTracker {
+    pub fn new(root_directory: &Path, source: IssueSource) -> IssueTracker {
+        IssueTracker {
+            root_directory: root_directory.into(),
+            source,
+            export: None,
+            cache: None,
+            fetched: HashMap::new(),
+        }
+    }
+
+    /// The state of an issue, i.e. `open` or `closed`
+    pub fn state(&mut self, number: u64) -> Result<String> {
+        if let Some(file) = self.source.file.clone() {
+            if self.export.is_none() {
+                self.export = Some(
+                    read_export(&self.root_directory.join(&file)).map_err(|err| err.to_string()),
+                );
+            }
+
+            return match self.export.as_ref().expect("Export was read above") {
+                Ok(states) => states
+                    .get(&number)
+                    .cloned()
+                    .ok_or_else(|| eyre!("issue #{} is not in {}", number, file.display())),
+                Err(err) => Err(eyre!("{}", err)),
+            };
+        }
+
+        let url = match self.source.url.clone() {
+            Some(url) => url,
+            None => {
+                return Err(eyre!(
+                    "no issue source is configured, set `file` or `url` under `[issues]` in .todo-ci.toml"
+                ))
+            }
+        };
+
+        if let Some(state) = self.fetched.get(&number) {
+            return state.clone().map_err(|err| eyre!("{}", err));
+        }
+        if self.cache.is_none() {
+            self.cache = Some(self.read_cache());
+        }
+        let cache = self.cache.as_mut().expect("Cache was read above");
+
+        let state = match fetch(&url, number, self.source.token_env.as_deref()) {
+            Ok(state) => {
+                cache.insert(number, state.clone());
+                Ok(state)
+            }
+            // Offline fallback to the last known state
+            Err(err) => cache
+                .get(&number)
+                .cloned()
+                .ok_or_else(|| format!("could not fetch issue #{}: {}", number, err)),
+        };
+        self.fetched.insert(number, state.clone());
+
+        state.map_err(|err| eyre!("{}", err))
+    }
+
+    fn read_cache(&self) -> HashMap<u64, String> {
+        self.source
+            .cache_file
+            .as_ref()
+            .and_then(|cache_file| {
+                std::fs::read_to_string(self.root_directory.join(cache_file)).ok()
+            })
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the states fetched from `url` to the cache file, if any were fetched
+    pub fn write_cache(&self) {
+        if self.fetched.is_empty() {
+            return;
+        }
+        if let (Some(cache_file), Some(cache)) = (&self.source.cache_file, &self.cache) {
+            // The cache is only an optimization, so failing to write it is not an error
+            if let Ok(contents) = serde_json::to_string_pretty(cache) {
+                let _ = std::fs::write(self.root_directory.join(cache_file), contents);
+            }
+        }
+    }
+}
+
+/// An issue as exported or returned by the GitHub & GitLab REST APIs
+#[derive(Debug, Deserialize)]
+struct Issue {
+    /// GitLab uses `iid` for the project-scoped issue number
+    #[serde(alias = "iid")]
+    number: Option<u64>,
+    state: String,
+}
+
+fn read_export(path: &Path) -> Result<HashMap<u64, String>> {
+    let contents = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("could not read {}", path.display()))?;
+
+    let issues: Vec<Issue> = if matches!(path.extension(), Some(extension) if extension == "csv") {
+        csv::Reader::from_reader(contents.as_bytes())
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .wrap_err_with(|| format!("could not parse {}", path.display()))?
+    } else {
+        serde_json::from_str(&contents)
+            .wrap_err_with(|| format!("could not parse {}", path.display()))?
+    };
+
+    Ok(issues
+        .into_iter()
+        .filter_map(|issue| Some((issue.number?, normalize_state(&issue.state))))
+        .collect())
+}
+
+fn fetch(url: &str, number: u64, token_env: Option<&str>) -> Result<String> {
+    let mut request = ureq::get(&format!("{}/{}", url.trim_end_matches('/'), number))
+        .timeout(Duration::from_secs(10))
+        .set("Accept", "application/json")
+        .set("User-Agent", "todo-ci");
+    if let Some(token) = token_env.and_then(|token_env| std::env::var(token_env).ok()) {
+        request = request.set("Authorization", &format!("Bearer {}", token));
+    }
+
+    let body = request.call()?.into_string()?;
+    let issue: Issue = serde_json::from_str(&body).wrap_err("unexpected response")?;
+
+    Ok(normalize_state(&issue.state))
+}
+
+/// GitLab calls open issues `opened`
+fn normalize_state(state: &str) -> String {
+    match state.to_lowercase().as_str() {
+        "opened" => "open".to_string(),
+        state => state.to_string(),
+    }
+}
@@ -0,0 +1,400 @@
+use std::{ops::Range, path::Path};
+
+/// Comment syntax of a language, used to only match TODOs that are inside comments
+#[derive(Debug)]
+pub struct Language {
+    pub name: &'static str,
+    extensions: &'static [&'static str],
+    file_names: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+    /// String delimiters - comment markers inside of strings are not comments
+    strings: &'static [&'static str],
+    /// String delimiters of strings that may span several lines, the others are closed at the end of a line
+    multi_line_strings: &'static [&'static str],
+    /// Rust's raw strings (`r"..."`, `r#"..."#`, `br"..."`) and char literals, told apart from lifetimes
+    rust_literals: bool,
+}
+
+const C_LIKE_LINE: &[&str] = &["//"];
+const C_LIKE_BLOCK: &[(&str, &str)] = &[("/*", "*/")];
+const HASH_LINE: &[&str] = &["#"];
+const QUOTES: &[&str] = &["\"", "'"];
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        name: "rust",
+        extensions: &["rs"],
+        file_names: &[],
+        line_comments: C_LIKE_LINE,
+        block_comments: C_LIKE_BLOCK,
+        // `'` is lexed as char literals instead, as lifetimes would be mistaken for unterminated strings
+        strings: &["\""],
+        multi_line_strings: &["\""],
+        rust_literals: true,
+    },
+    Language {
+        name: "c-family",
+        extensions: &[
+            "c", "h", "cc", "cpp", "cxx", "hpp", "hxx", "cs", "java", "kt", "kts", "scala",
+            "swift", "dart", "groovy", "gradle", "go", "zig", "proto", "m",
+        ],
+        file_names: &[],
+        line_comments: C_LIKE_LINE,
+        block_comments: C_LIKE_BLOCK,
+        strings: QUOTES,
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "javascript",
+        extensions: &[
+            "js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts", "vue", "svelte",
+        ],
+        file_names: &[],
+        line_comments: C_LIKE_LINE,
+        block_comments: C_LIKE_BLOCK,
+        strings: &["\"", "'", "`"],
+        multi_line_strings: &["`"],
+        rust_literals: false,
+    },
+    Language {
+        name: "css",
+        extensions: &["css"],
+        file_names: &[],
+        // Plain CSS has no line comments, so unquoted `url(http://...)` isn't one
+        line_comments: &[],
+        block_comments: C_LIKE_BLOCK,
+        strings: QUOTES,
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "scss",
+        extensions: &["scss", "sass", "less"],
+        file_names: &[],
+        line_comments: C_LIKE_LINE,
+        block_comments: C_LIKE_BLOCK,
+        strings: QUOTES,
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "php",
+        extensions: &["php"],
+        file_names: &[],
+        line_comments: &["//", "#"],
+        block_comments: C_LIKE_BLOCK,
+        strings: QUOTES,
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "python",
+        extensions: &["py", "pyi"],
+        file_names: &[],
+        line_comments: HASH_LINE,
+        // Docstrings are treated as comments
+        block_comments: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+        strings: QUOTES,
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "hash-comments",
+        extensions: &[
+            "sh", "bash", "zsh", "fish", "rb", "pl", "pm", "r", "ex", "exs", "nim", "cr", "jl",
+            "tf", "hcl", "ps1", "cmake", "yml", "yaml", "toml", "conf",
+        ],
+        file_names: &[
+            "Dockerfile",
+            "Makefile",
+            "CMakeLists.txt",
+            "Gemfile",
+            "Rakefile",
+        ],
+        line_comments: HASH_LINE,
+        block_comments: &[],
+        strings: QUOTES,
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "ini",
+        extensions: &["ini", "cfg"],
+        file_names: &[],
+        line_comments: &[";", "#"],
+        block_comments: &[],
+        strings: &[],
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "lua",
+        extensions: &["lua"],
+        file_names: &[],
+        line_comments: &["--"],
+        block_comments: &[("--[[", "]]")],
+        strings: QUOTES,
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "sql",
+        extensions: &["sql"],
+        file_names: &[],
+        line_comments: &["--"],
+        block_comments: C_LIKE_BLOCK,
+        strings: &["'"],
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "haskell",
+        extensions: &["hs", "elm"],
+        file_names: &[],
+        line_comments: &["--"],
+        block_comments: &[("{-", "-}")],
+        strings: &["\""],
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "lisp",
+        extensions: &["clj", "cljs", "el", "lisp", "scm", "rkt"],
+        file_names: &[],
+        line_comments: &[";"],
+        block_comments: &[],
+        strings: &["\""],
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "percent-comments",
+        extensions: &["erl", "hrl", "tex"],
+        file_names: &[],
+        line_comments: &["%"],
+        block_comments: &[],
+        strings: &["\""],
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+    Language {
+        name: "markup",
+        extensions: &["html", "htm", "xml", "xaml", "svg", "md", "markdown"],
+        file_names: &[],
+        line_comments: &[],
+        block_comments: &[("<!--", "-->")],
+        strings: &[],
+        multi_line_strings: &[],
+        rust_literals: false,
+    },
+];
+
+/// A comment on a single line of text
+#[derive(Debug, Clone)]
+pub struct Comment {
+    /// Starts at the opening comment marker and ends before a closing block comment marker
+    pub range: Range<usize>,
+    /// Marker of a line comment, `None` for block comments
+    pub line_marker: Option<&'static str>,
+    /// Block comment started on a previous line
+    pub continued: bool,
+    /// Block comment continues on the next line
+    pub unterminated: bool,
+}
+
+/// What the lexer is currently inside of, carried over between lines
+enum LexState {
+    Code,
+    BlockComment(usize),
+    String(usize),
+    /// Raw string closed by `"` and the number of `#`s it was opened with
+    RawString(usize),
+}
+
+impl Language {
+    /// Detects the language of a file from its file name or extension
+    pub fn detect(path: &Path) -> Option<&'static Language> {
+        let file_name = path.file_name()?.to_str()?;
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+
+        LANGUAGES.iter().find(|language| {
+            language.file_names.contains(&file_name)
+                || matches!(&extension, Some(ext) if language.extensions.contains(&ext.as_str()))
+        })
+    }
+
+    /// Comments in each line of `text`
+    pub fn comments(&self, text: &str) -> Vec<Vec<Comment>> {
+        let mut state = LexState::Code;
+        let mut lines = vec![];
+
+        for line in text.split('\n') {
+            let mut comments = vec![];
+            let mut i = 0;
+            // Comments continued from a previous line start at the beginning of the line
+            let mut comment_start = 0;
+            let mut continued = matches!(state, LexState::BlockComment(_));
+
+            while i < line.len() {
+                let rest = &line[i..];
+                match state {
+                    LexState::Code => {
+                        if let Some(index) = self
+                            .block_comments
+                            .iter()
+                            .position(|(open, _)| rest.starts_with(open))
+                        {
+                            comment_start = i;
+                            continued = false;
+                            i += self.block_comments[index].0.len();
+                            state = LexState::BlockComment(index);
+                        } else if let Some(marker) = self
+                            .line_comments
+                            .iter()
+                            .find(|marker| rest.starts_with(**marker))
+                        {
+                            comments.push(Comment {
+                                range: i..line.len(),
+                                line_marker: Some(marker),
+                                continued: false,
+                                unterminated: false,
+                            });
+                            break;
+                        } else if let Some((length, hashes)) = self.raw_string_start(line, i) {
+                            i += length;
+                            state = LexState::RawString(hashes);
+                        } else if self.rust_literals && rest.starts_with('\'') {
+                            i += char_literal_len(rest);
+                        } else if let Some(index) = self
+                            .strings
+                            .iter()
+                            .position(|quote| rest.starts_with(quote))
+                        {
+                            i += self.strings[index].len();
+                            state = LexState::String(index);
+                        } else {
+                            i += next_char_len(rest);
+                        }
+                    }
+                    LexState::BlockComment(index) => {
+                        let close = self.block_comments[index].1;
+                        match rest.find(close) {
+                            Some(offset) => {
+                                comments.push(Comment {
+                                    range: comment_start..i + offset,
+                                    line_marker: None,
+                                    continued,
+                                    unterminated: false,
+                                });
+                                i += offset + close.len();
+                                state = LexState::Code;
+                            }
+                            None => {
+                                comments.push(Comment {
+                                    range: comment_start..line.len(),
+                                    line_marker: None,
+                                    continued,
+                                    unterminated: true,
+                                });
+                                break;
+                            }
+                        }
+                    }
+                    LexState::RawString(hashes) => {
+                        let close = format!("\"{}", "#".repeat(hashes));
+                        match rest.find(&close) {
+                            Some(offset) => {
+                                i += offset + close.len();
+                                state = LexState::Code;
+                            }
+                            None => break,
+                        }
+                    }
+                    LexState::String(index) => {
+                        let quote = self.strings[index];
+                        if let Some(escaped) = rest.strip_prefix('\\') {
+                            i += 1 + escaped.chars().next().map_or(0, char::len_utf8);
+                        } else if rest.starts_with(quote) {
+                            i += quote.len();
+                            state = LexState::Code;
+                        } else {
+                            i += next_char_len(rest);
+                        }
+                    }
+                }
+            }
+
+            if let LexState::String(index) = state {
+                if !self.multi_line_strings.contains(&self.strings[index]) {
+                    state = LexState::Code;
+                }
+            }
+
+            // Blank lines inside of a block comment
+            if line.is_empty() && continued {
+                comments.push(Comment {
+                    range: 0..0,
+                    line_marker: None,
+                    continued,
+                    unterminated: true,
+                });
+            }
+
+            lines.push(comments);
+        }
+
+        lines
+    }
+
+    /// Length of the prefix up to & including the opening `"` of a raw string starting at `i`, and its
+    /// number of `#`s - `None` if no raw string starts there
+    fn raw_string_start(&self, line: &str, i: usize) -> Option<(usize, usize)> {
+        if !self.rust_literals
+            || matches!(line[..i].chars().next_back(), Some(c) if c.is_alphanumeric() || c == '_')
+        {
+            return None;
+        }
+
+        let rest = &line[i..];
+        let hashes_and_quote = rest.strip_prefix("br").or_else(|| rest.strip_prefix('r'))?;
+        let quote = hashes_and_quote.trim_start_matches('#');
+        // Raw identifiers like `r#type` aren't followed by a `"`
+        if !quote.starts_with('"') {
+            return None;
+        }
+
+        Some((
+            rest.len() - quote.len() + 1,
+            hashes_and_quote.len() - quote.len(),
+        ))
+    }
+}
+
+/// Length of the char literal at the start of `text`, or of just its `'` for lifetimes & labels like `'a`
+fn char_literal_len(text: &str) -> usize {
+    let inner = &text[1..];
+    if let Some(escaped) = inner.strip_prefix('\\') {
+        // Escapes like `'\''`, `'\n'` or `'\u{1F600}'` end at the first `'` after the escaped char
+        let escaped_len = next_char_len(escaped);
+        return match escaped.get(escaped_len..).and_then(|rest| rest.find('\'')) {
+            Some(offset) => 2 + escaped_len + offset + 1,
+            None => 1,
+        };
+    }
+
+    let char_len = next_char_len(inner);
+    if matches!(inner.get(char_len..), Some(rest) if rest.starts_with('\'')) {
+        1 + char_len + 1
+    } else {
+        1
+    }
+}
+
+fn next_char_len(text: &str) -> usize {
+    text.chars().next().map_or(1, char::len_utf8)
+}
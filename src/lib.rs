@@ -1,36 +1,203 @@
+pub mod archive;
+pub mod attributes;
 pub mod cli;
+pub mod condition;
+pub mod config;
 pub mod core;
+pub mod deadline;
 pub mod display;
+pub mod encoding;
+pub mod exit_code;
+pub mod explain;
+pub mod git;
+pub mod issue;
+pub mod language;
+pub mod near_miss;
+pub mod paths;
+pub mod policy;
+pub mod project;
+pub mod rules;
+pub mod severity;
+pub mod source;
+pub mod suppression;
+pub mod workspace;
 
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
 use clap::Parser;
-use eyre::Result;
+use cli::{Command, DisplayMode};
+use config::Config;
+use eyre::{eyre, Result};
+use source::SourceKind;
 use termcolor::{ColorChoice, StandardStream};
 
 /// Main entry point of application - is seperate from main.rs and as a library for more ergonomic testing
-pub fn run() -> Result<()> {
+///
+/// Returns the exit code, see `exit_code`
+pub fn run() -> Result<i32> {
     // Get CLI args
     let args = cli::Args::parse();
 
+    let root_directories = match &args.command {
+        Some(Command::Files { root_directory }) | Some(Command::Explain { root_directory, .. }) => {
+            vec![root_directory.clone()]
+        }
+        None => args.root_directories.clone(),
+    };
+    // A root directory of `-` reads a single file from stdin, with the current directory as the root
+    let reads_stdin = args.command.is_none()
+        && root_directories
+            .iter()
+            .any(|root_directory| root_directory == Path::new("-"));
+    if reads_stdin && root_directories.len() > 1 {
+        return Err(eyre!("`-` can't be used with other root directories"));
+    }
+    let root_directories = if reads_stdin {
+        vec![PathBuf::from("./")]
+    } else {
+        root_directories
+    };
+    // The first root directory's configuration applies to the combined results
+    let root_directory = root_directories[0].clone();
+    let searches_packages = root_directories.len() > 1 || args.workspace;
+
+    let source = if reads_stdin {
+        if args.revision.is_some() || args.staged || args.files_from.is_some() || args.workspace {
+            return Err(eyre!(
+                "--rev, --staged, --files-from & --workspace can't be used when reading from stdin"
+            ));
+        }
+        match &args.stdin_filename {
+            Some(file_name) => SourceKind::Stdin(file_name.clone()),
+            None => {
+                return Err(eyre!(
+                    "--stdin-filename is required when reading from stdin"
+                ))
+            }
+        }
+    } else if let Some(files_from) = &args.files_from {
+        if searches_packages {
+            return Err(eyre!(
+                "--files-from can't be used with several root directories or --workspace"
+            ));
+        }
+        SourceKind::FileList(source::read_file_list(files_from)?)
+    } else if let Some(revision) = &args.revision {
+        SourceKind::Revision(revision.clone())
+    } else if args.staged {
+        SourceKind::Index
+    } else {
+        SourceKind::WorkingDirectory
+    };
+
+    // Load project configuration
+    let config = Config::load(&root_directory, args.config.as_deref())?;
+
     // Run todo search
-    let search_results = core::search(
-        args.root_directory,
-        args.no_ignore,
-        args.ignore_pattern,
-        &args.timezone_offset,
+    let base_options = core::SearchOptions {
+        no_ignore: args.no_ignore,
+        hidden: args.hidden,
+        no_gitignore: args.no_gitignore,
+        no_global_ignore: args.no_global_ignore,
+        no_parent_ignore: args.no_parent_ignore,
+        no_gitattributes: args.no_gitattributes,
+        follow_symlinks: args.follow_symlinks,
+        max_depth: args.max_depth,
+        one_file_system: args.one_file_system,
+        nested_repos: args.nested_repos,
+        source,
+        ignore_file_names: args.ignore_file_names,
+        include: args.include,
+        exclude: args.exclude,
+        file_types: args.file_types,
+        fixed_offset: args.timezone_offset,
+        encoding_mode: args.encoding,
+        anywhere: args.anywhere,
+        ..Default::default()
+    };
+    let search_options = base_options.clone().with_config(&config);
+    let mut stdout = StandardStream::stdout(ColorChoice::Always);
+
+    match &args.command {
+        Some(Command::Files { .. }) => {
+            let files = core::files(root_directory, &search_options)?;
+            let mut stderr = StandardStream::stderr(ColorChoice::Always);
+            display::print_files(&mut stdout, &mut stderr, &files);
+            return Ok(exit_code::SUCCESS);
+        }
+        Some(Command::Explain { path, .. }) => {
+            let explanation = explain::explain(&root_directory, path, &search_options)?;
+            display::print_explanation(&mut stdout, &explanation);
+            return Ok(exit_code::SUCCESS);
+        }
+        None => (),
+    }
+
+    let search_results = if searches_packages {
+        let mut packages = vec![];
+        for root_directory in &root_directories {
+            let root_config = Config::load(root_directory, args.config.as_deref())?;
+            let root_packages = if args.workspace {
+                workspace::packages(root_directory)?
+            } else {
+                vec![root_directory.clone()]
+            };
+
+            // Workspace packages use the workspace's configuration, unless they have their own
+            for package in root_packages {
+                let options = if args.config.is_none()
+                    && package != *root_directory
+                    && package.join(Config::FILE_NAME).is_file()
+                {
+                    base_options
+                        .clone()
+                        .with_config(&Config::load(&package, None)?)
+                } else {
+                    base_options.clone().with_config(&root_config)
+                };
+                packages.push((package, options));
+            }
+        }
+        core::search_packages(packages)?
+    } else {
+        core::search(root_directory.clone(), &search_options)?
+    };
+    let policy_violations = policy::check(
+        &config.budget,
+        &config.ratchet,
+        &search_results,
+        &root_directory,
+        &search_options,
     )?;
 
     // Print results of search
-    let mut stdout = StandardStream::stdout(ColorChoice::Always);
-    display::print(
-        &mut stdout,
-        args.display_mode,
-        &search_results,
-        &args.timezone_offset,
-    );
+    if matches!(args.display_mode, DisplayMode::Json) {
+        display::print_json(&mut stdout, &search_results, &policy_violations);
+    } else {
+        display::print(
+            &mut stdout,
+            args.display_mode,
+            &search_results,
+            &args.timezone_offset,
+        );
+        display::print_suppressions(&mut stdout, &search_results.suppressions);
+        display::print_policy_violations(&mut stdout, &policy_violations);
+    }
 
-    if search_results.statistics.overdue_todo_count > 0 && !args.no_error {
-        std::process::exit(1)
-    };
+    if args.no_error {
+        return Ok(exit_code::SUCCESS);
+    }
 
-    Ok(())
+    let today = Utc::now()
+        .with_timezone(&args.timezone_offset)
+        .date()
+        .naive_local();
+    Ok(exit_code::from_results(
+        &search_results,
+        &args.fail_on,
+        args.due_soon_days,
+        today,
+        &policy_violations,
+    ))
 }
@@ -1,4 +1,13 @@
-fn main() -> eyre::Result<()> {
-    todo_ci::run()?;
-    Ok(())
+use todo_ci::exit_code;
+
+fn main() {
+    let code = match todo_ci::run() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            exit_code::ERROR
+        }
+    };
+
+    std::process::exit(code)
 }
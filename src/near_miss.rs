@@ -0,0 +1,158 @@
+use chrono::NaiveDate;
+
+use crate::{
+    condition::Condition,
+    deadline::{Deadline, RelativeOffset},
+    severity::Severity,
+};
+use grep::{
+    matcher::{Captures, Matcher},
+    regex::RegexMatcher,
+};
+
+/// Loosely matches anything that looks like it was meant to be a TODO,
+// todo-ci: ignore-next-line -- examples of near-misses
+/// i.e. `@TODO(2024-01-01):`, `@todo (2024-01-01):`, `@todo[2024-1-1]` or `@todo(2024-01-01) description`
+pub const PATTERN: &str = r"(?i)@\s*todo\s*[(\[{<][^)\]}>\n]*[)\]}>]?.*";
+
+/// Matches the start of a near-miss annotation up to its opening bracket - the rest is split by `diagnose`,
+/// as the closing bracket has to pair with the opening one
+pub const CAPTURE_PATTERN: &str =
+    r"(?i)@(?P<space1>\s*)(?P<keyword>todo)(?P<space2>\s*)(?P<open>[(\[{<])";
+
+const BRACKETS: &[char] = &['(', ')', '[', ']', '{', '}', '<', '>'];
+const CLOSING_BRACKETS: &[char] = &[')', ']', '}', '>'];
+
+/// A TODO annotation that does not follow the expected format
+#[derive(Debug, PartialEq, Eq)]
+pub struct NearMiss {
+    /// Why the annotation is not a valid TODO
+    pub reason: String,
+    /// The annotation as it should have been written, unless it can't be guessed
+    pub suggestion: Option<String>,
+}
+
+/// Diagnoses why `text` containing a loosely matched TODO is not a valid TODO, with `matcher` compiled
+/// from `CAPTURE_PATTERN`
+///
+/// Returns `None` if `text` does not contain a near-miss annotation
+pub fn diagnose(matcher: &RegexMatcher, text: &str, date_formats: &[String]) -> Option<NearMiss> {
+    let mut captures = matcher.new_captures().expect("Regex should be valid");
+    if !matcher
+        .captures(text.as_bytes(), &mut captures)
+        .expect("Regex should be valid")
+    {
+        return None;
+    }
+
+    let group = |name: &str| {
+        matcher
+            .capture_index(name)
+            .and_then(|index| captures.get(index))
+            .map_or("", |range| &text[range])
+    };
+
+    // The date ends at the bracket pairing with the opening one - or else any closing bracket
+    let rest = &text[captures.get(0).expect("Regex should be valid").end()..];
+    let paired_close = match group("open") {
+        "(" => ')',
+        "[" => ']',
+        "{" => '}',
+        _ => '>',
+    };
+    let date_end = rest
+        .find(paired_close)
+        .or_else(|| rest.find(CLOSING_BRACKETS))
+        .unwrap_or(rest.len());
+    let date_group = &rest[..date_end];
+    let close = rest[date_end..]
+        .chars()
+        .next()
+        .map_or(String::new(), String::from);
+    let after_close = &rest[date_end + close.len()..];
+    let (colon, description) = match after_close.trim_start().strip_prefix(':') {
+        Some(description) => (
+            &after_close[..after_close.len() - description.len()],
+            description,
+        ),
+        None => ("", after_close),
+    };
+
+    let mut reasons = vec![];
+
+    if !group("space1").is_empty() || group("keyword") != "todo" {
+        reasons.push(format!(
+            "`@{}{}` should be written as `@todo`",
+            group("space1"),
+            group("keyword")
+        ));
+    }
+    if !group("space2").is_empty() {
+        reasons.push("there should be no space before the date".to_string());
+    }
+    if group("open") != "(" || close != ")" {
+        reasons.push(format!(
+            "the date should be wrapped in `(...)` instead of `{}...{}`",
+            group("open"),
+            close
+        ));
+    }
+
+    let (date_string, severity) = Severity::split(date_group.trim());
+    let date = if Deadline::parse(date_string, date_formats).is_some()
+        || RelativeOffset::parse(date_string).is_some()
+        || Condition::is_condition(date_string)
+    {
+        date_string.to_string()
+    } else {
+        match NaiveDate::parse_from_str(date_string, "%Y-%m-%d") {
+            // Leniently parsed, i.e. `2024-8-1`
+            Ok(date) => {
+                reasons.push(format!("{} should be formatted as YYYY-MM-DD", date_string));
+                date.format("%Y-%m-%d").to_string()
+            }
+            Err(_) => {
+                reasons.push(format!("{} is not a valid date", date_string));
+                "YYYY-MM-DD".to_string()
+            }
+        }
+    };
+
+    if colon.is_empty() {
+        reasons.push("the date should be followed by a colon".to_string());
+    } else if colon != ":" {
+        reasons.push("there should be no space before the colon".to_string());
+    }
+
+    if reasons.is_empty() {
+        return None;
+    }
+
+    let mut reason = reasons.join(", ");
+    reason.push('.');
+
+    let date = match severity {
+        Some(severity) => format!("{}, {}", date, severity),
+        None => date,
+    };
+
+    // A date containing other brackets is likely not meant as one, i.e. a placeholder like `<date>`
+    let suggestion = if date_group.contains(BRACKETS) {
+        None
+    } else {
+        Some(format!("@todo({}): {}", date, description.trim()))
+    };
+
+    Some(NearMiss {
+        reason: capitalize(&reason),
+        suggestion,
+    })
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,33 @@
+use eyre::{Result, WrapErr};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// Compiles a glob for paths relative to the root directory - a trailing `/` matches everything in a directory
+pub fn glob(pattern: &str) -> Result<Glob> {
+    let expanded = match pattern.strip_suffix('/') {
+        Some(directory) => format!("{}/**", directory),
+        None => pattern.to_string(),
+    };
+
+    Glob::new(&expanded).wrap_err_with(|| format!("Invalid glob {}", pattern))
+}
+
+/// Compiles globs for paths relative to the root directory into a set, see `glob`
+pub fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(glob(pattern)?);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Escapes a path relative to the root directory, so a glob of it only matches the path itself - i.e. for a
+/// directory named `[lib]`
+pub fn escape(path: &str) -> String {
+    path.chars()
+        .map(|c| match c {
+            '*' | '?' | '[' | ']' | '{' | '}' => format!("[{}]", c),
+            _ => c.to_string(),
+        })
+        .collect()
+}
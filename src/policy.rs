@@ -0,0 +1,252 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{SearchOptions, SearchResult, Todo, TodoState},
+    paths,
+    source::SourceKind,
+};
+
+/// Maximum numbers of TODOs, configured under `[budget]` - malformed TODOs are not counted
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Budget {
+    /// Maximum number of TODOs in total
+    pub max_todos: Option<usize>,
+    /// Maximum number of TODOs in a single file
+    pub max_todos_per_file: Option<usize>,
+    /// Maximum numbers of TODOs in paths matching a glob
+    pub paths: Vec<PathBudget>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PathBudget {
+    /// Glob relative to the root directory, i.e. `tests/**` - a trailing `/` matches everything in a directory
+    pub path: String,
+    /// Maximum number of TODOs in the path, counting the existing ones
+    pub max_todos: Option<usize>,
+    /// Maximum number of TODOs added to the path since the count stored by the ratchet
+    pub max_new_todos: Option<usize>,
+}
+
+/// Prevents the numbers of TODOs from increasing, configured under `[ratchet]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Ratchet {
+    /// File to store the previous numbers of TODOs in, relative to the root directory
+    ///
+    /// It is created if it doesn't exist yet and lowered whenever the numbers decrease.
+    pub file: Option<PathBuf>,
+}
+
+/// Numbers of TODOs stored by the ratchet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Counts {
+    todos: usize,
+    overdue: usize,
+    /// Numbers of TODOs in the paths of budgets with `max_new_todos`, by their glob
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    paths: BTreeMap<String, usize>,
+}
+
+/// Checks the TODOs found against the tags & owners their path rules require, the budget & the ratchet,
+/// returning a description of each violation
+///
+/// Budgets & the ratchet don't apply to files listed with `--files-from` or read from stdin, path budgets &
+/// the ratchet don't apply to several root directories or packages, and the ratchet only applies to full
+/// scans of the working directory, see `SearchOptions::is_full_scan`.
+pub fn check(
+    budget: &Budget,
+    ratchet: &Ratchet,
+    results: &SearchResult,
+    root_directory: &Path,
+    options: &SearchOptions,
+) -> Result<Vec<String>> {
+    let todos: Vec<&Todo> = results
+        .todos
+        .iter()
+        .filter(|todo| !matches!(todo.state, TodoState::Malformed))
+        .collect();
+    let mut violations = vec![];
+
+    // Rules that can't fail the build don't require anything either
+    for todo in &todos {
+        if let Some(rule) = todo.rule.as_ref().filter(|rule| rule.fail != Some(false)) {
+            for violation in rule.violations(&todo.tags, todo.owner.as_deref()) {
+                violations.push(format!(
+                    "{}:{}: {}",
+                    todo.file.display(),
+                    todo.line_number,
+                    violation
+                ));
+            }
+        }
+    }
+
+    if matches!(
+        options.source,
+        SourceKind::FileList(_) | SourceKind::Stdin(_)
+    ) {
+        return Ok(violations);
+    }
+
+    if let Some(max_todos) = budget.max_todos {
+        if todos.len() > max_todos {
+            violations.push(format!(
+                "{} todo(s) were found, but the budget is {}",
+                todos.len(),
+                max_todos
+            ));
+        }
+    }
+
+    if let Some(max_todos_per_file) = budget.max_todos_per_file {
+        let mut todos_per_file: HashMap<&Path, usize> = HashMap::new();
+        for todo in &todos {
+            *todos_per_file.entry(&todo.file).or_default() += 1;
+        }
+
+        let mut files: Vec<(&Path, usize)> = todos_per_file
+            .into_iter()
+            .filter(|(_, count)| *count > max_todos_per_file)
+            .collect();
+        files.sort();
+        for (file, count) in files {
+            violations.push(format!(
+                "{} todo(s) were found in {}, but the budget is {} per file",
+                count,
+                file.display(),
+                max_todos_per_file
+            ));
+        }
+    }
+
+    // Results of several root directories or packages don't belong to the root directory the path budgets
+    // & the ratchet are configured for - their paths aren't relative to it, and their counts would be mixed
+    // into its ratchet
+    let single_root = results.packages.is_empty();
+
+    let mut path_counts = BTreeMap::new();
+    for path_budget in budget.paths.iter().filter(|_| single_root) {
+        let glob = paths::glob(&path_budget.path)?.compile_matcher();
+
+        let count = todos
+            .iter()
+            .filter(|todo| {
+                glob.is_match(todo.file.strip_prefix(root_directory).unwrap_or(&todo.file))
+            })
+            .count();
+        if let Some(max_todos) = path_budget.max_todos.filter(|max_todos| count > *max_todos) {
+            violations.push(format!(
+                "{} todo(s) were found in {}, but the budget is {}",
+                count, path_budget.path, max_todos
+            ));
+        }
+
+        if path_budget.max_new_todos.is_some() {
+            if ratchet.file.is_none() {
+                return Err(eyre!(
+                    "The max-new-todos budget of {} requires a [ratchet] file to compare against",
+                    path_budget.path
+                ));
+            }
+            path_counts.insert(path_budget.path.clone(), count);
+        }
+    }
+
+    // Archives are searched as a full scan, but the ratchet can't be stored in them
+    let ratchet_file = ratchet
+        .file
+        .as_ref()
+        .filter(|_| single_root && options.is_full_scan() && root_directory.is_dir());
+    if let Some(file) = ratchet_file {
+        let current = Counts {
+            todos: todos.len(),
+            overdue: results.statistics.overdue_todo_count as usize,
+            paths: path_counts,
+        };
+        violations.append(&mut check_ratchet(
+            &root_directory.join(file),
+            current,
+            &budget.paths,
+        )?);
+    }
+
+    Ok(violations)
+}
+
+fn check_ratchet(file: &Path, current: Counts, path_budgets: &[PathBudget]) -> Result<Vec<String>> {
+    let previous: Option<Counts> = match std::fs::read_to_string(file) {
+        Ok(contents) => Some(
+            serde_json::from_str(&contents)
+                .wrap_err_with(|| format!("Could not parse {}", file.display()))?,
+        ),
+        Err(_) => None,
+    };
+
+    let mut violations = vec![];
+    if let Some(previous) = &previous {
+        if current.todos > previous.todos {
+            violations.push(format!(
+                "the number of todos increased from {} to {}",
+                previous.todos, current.todos
+            ));
+        }
+        if current.overdue > previous.overdue {
+            violations.push(format!(
+                "the number of overdue todos increased from {} to {}",
+                previous.overdue, current.overdue
+            ));
+        }
+
+        for path_budget in path_budgets {
+            let (count, previous_count, max_new_todos) = match (
+                current.paths.get(&path_budget.path),
+                previous.paths.get(&path_budget.path),
+                path_budget.max_new_todos,
+            ) {
+                (Some(count), Some(previous_count), Some(max_new_todos)) => {
+                    (*count, *previous_count, max_new_todos)
+                }
+                // Paths without a stored count yet start from the current one
+                _ => continue,
+            };
+            let new_todos = count.saturating_sub(previous_count);
+            if new_todos > max_new_todos {
+                violations.push(format!(
+                    "{} new todo(s) were found in {}, but the budget is {} new todo(s)",
+                    new_todos, path_budget.path, max_new_todos
+                ));
+            }
+        }
+    }
+
+    // The ratchet only ever tightens
+    let lowered = match &previous {
+        Some(previous) => Counts {
+            todos: previous.todos.min(current.todos),
+            overdue: previous.overdue.min(current.overdue),
+            paths: current
+                .paths
+                .iter()
+                .map(|(path, count)| {
+                    let previous_count = previous.paths.get(path).copied().unwrap_or(*count);
+                    (path.clone(), previous_count.min(*count))
+                })
+                .collect(),
+        },
+        None => current,
+    };
+    if previous.as_ref() != Some(&lowered) {
+        std::fs::write(file, serde_json::to_string_pretty(&lowered)?)
+            .wrap_err_with(|| format!("Could not write {}", file.display()))?;
+    }
+
+    Ok(violations)
+}
@@ -0,0 +1,404 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use eyre::{eyre, Result, WrapErr};
+use grep::{
+    matcher::{Captures, Matcher},
+    regex::RegexMatcher,
+};
+use semver::Version;
+
+use crate::{
+    git,
+    issue::{IssueSource, IssueTracker},
+    source::SourceKind,
+    workspace,
+};
+
+/// Where to read the project's own version from, if not from a known manifest
+#[derive(Debug, Clone, Default)]
+pub struct VersionSource {
+    /// File containing the version, relative to the root directory
+    pub file: Option<PathBuf>,
+    /// Regex with a `version` capture group (or else the whole match) to find the version in `file`
+    pub pattern: Option<String>,
+}
+
+/// Information about the scanned project that conditions of TODOs are checked against
+///
+/// Everything is read lazily, as most projects only use dates. Files are read from the same tree as
+/// the searched files, so conditions of a `--rev` or `--staged` search hold as of that tree.
+#[derive(Debug)]
+pub struct Project {
+    root_directory: PathBuf,
+    source: SourceKind,
+    version_source: VersionSource,
+    version: Option<Result<Version, String>>,
+    dependencies: Option<Result<HashMap<String, Version>, String>>,
+    rust_version: Option<Result<Version, String>>,
+    issues: IssueTracker,
+}
+
+impl Project {
+    pub fn new(
+        root_directory: &Path,
+        source: &SourceKind,
+        version_source: VersionSource,
+        issue_source: IssueSource,
+    ) -> Project {
+        Project {
+            root_directory: root_directory.into(),
+            source: source.clone(),
+            version_source,
+            version: None,
+            dependencies: None,
+            rust_version: None,
+            issues: IssueTracker::new(root_directory, issue_source),
+        }
+    }
+
+    /// Whether a path relative to the root directory exists
+    pub fn path_exists(&self, path: &Path) -> bool {
+        match &self.source {
+            SourceKind::Revision(revision) => {
+                git::path_exists(&self.root_directory, Some(revision), path)
+            }
+            SourceKind::Index => git::path_exists(&self.root_directory, None, path),
+            SourceKind::WorkingDirectory | SourceKind::FileList(_) | SourceKind::Stdin(_) => {
+                self.root_directory.join(path).exists()
+            }
+        }
+    }
+
+    /// Contents of a file by its path relative to the root directory
+    fn read(&self, path: &Path) -> Option<String> {
+        let contents = match &self.source {
+            SourceKind::Revision(revision) => {
+                git::show_file(&self.root_directory, Some(revision), path)?
+            }
+            SourceKind::Index => git::show_file(&self.root_directory, None, path)?,
+            SourceKind::WorkingDirectory | SourceKind::FileList(_) | SourceKind::Stdin(_) => {
+                std::fs::read(self.root_directory.join(path)).ok()?
+            }
+        };
+        String::from_utf8(contents).ok()
+    }
+
+    fn read_toml(&self, path: &Path) -> Option<toml::Value> {
+        toml::from_str(&self.read(path)?).ok()
+    }
+
+    /// The project's own version - from a configured file or else Cargo.toml, package.json or pyproject.toml
+    pub fn version(&mut self) -> Result<Version> {
+        if self.version.is_none() {
+            self.version = Some(self.read_version().map_err(|err| err.to_string()));
+        }
+
+        match self.version.as_ref().expect("Version was read above") {
+            Ok(version) => Ok(version.clone()),
+            Err(err) => Err(eyre!("{}", err)),
+        }
+    }
+
+    /// The project's minimum supported Rust version - from `rust-version` in Cargo.toml,
+    /// or else the channel of rust-toolchain.toml
+    pub fn rust_version(&mut self) -> Result<Version> {
+        if self.rust_version.is_none() {
+            self.rust_version = Some(self.read_rust_version().map_err(|err| err.to_string()));
+        }
+
+        match self
+            .rust_version
+            .as_ref()
+            .expect("Rust version was read above")
+        {
+            Ok(version) => Ok(version.clone()),
+            Err(err) => Err(eyre!("{}", err)),
+        }
+    }
+
+    fn read_rust_version(&self) -> Result<Version> {
+        if let Some(rust_version) = self.cargo_package_field("rust-version") {
+            return parse_version(&rust_version);
+        }
+
+        let channel = self
+            .read(Path::new("rust-toolchain.toml"))
+            .and_then(|contents| toml::from_str::<toml::Value>(&contents).ok())
+            .and_then(|toolchain| {
+                toolchain
+                    .get("toolchain")?
+                    .get("channel")?
+                    .as_str()
+                    .map(String::from)
+            })
+            // The legacy `rust-toolchain` file only contains the channel
+            .or_else(|| {
+                self.read(Path::new("rust-toolchain"))
+                    .map(|channel| channel.trim().to_string())
+            });
+
+        match channel {
+            Some(channel) => parse_version(&channel)
+                .wrap_err_with(|| format!("the toolchain channel `{}` is not a version", channel)),
+            None => Err(eyre!(
+                "no `rust-version` in Cargo.toml or toolchain channel in rust-toolchain.toml was found"
+            )),
+        }
+    }
+
+    /// A field of the `[package]` in Cargo.toml - or of the workspace's `[workspace.package]` if it is
+    /// inherited with `field.workspace = true`, or if Cargo.toml only has a `[workspace]`
+    fn cargo_package_field(&self, field: &str) -> Option<String> {
+        let manifest = self.read_toml(Path::new("Cargo.toml"))?;
+        let workspace_field = |manifest: &toml::Value| {
+            manifest
+                .get("workspace")?
+                .get("package")?
+                .get(field)?
+                .as_str()
+                .map(String::from)
+        };
+
+        let value = match manifest.get("package") {
+            Some(package) => package.get(field)?,
+            None => return workspace_field(&manifest),
+        };
+        if value
+            .get("workspace")
+            .and_then(|workspace| workspace.as_bool())
+            == Some(true)
+        {
+            let workspace_root =
+                workspace::cargo_root(&self.root_directory, &|path| self.read(path))?;
+            return workspace_field(&self.read_toml(&workspace_root.join("Cargo.toml"))?);
+        }
+        value.as_str().map(String::from)
+    }
+
+    /// The state of an issue, i.e. `open` or `closed`
+    pub fn issue_state(&mut self, number: u64) -> Result<String> {
+        self.issues.state(number)
+    }
+
+    /// Writes the issue states fetched during the search to the cache file
+    pub fn write_issue_cache(&self) {
+        self.issues.write_cache();
+    }
+
+    /// The resolved version of a dependency - from Cargo.lock, package-lock.json or poetry.lock
+    ///
+    /// If a dependency is resolved more than once, the highest version is used.
+    pub fn dependency_version(&mut self, name: &str) -> Result<Version> {
+        if self.dependencies.is_none() {
+            self.dependencies = Some(self.read_lockfiles().map_err(|err| err.to_string()));
+        }
+
+        let dependencies = match self
+            .dependencies
+            .as_ref()
+            .expect("Lockfiles were read above")
+        {
+            Ok(dependencies) => dependencies,
+            Err(err) => return Err(eyre!("{}", err)),
+        };
+
+        dependencies
+            .get(name)
+            .or_else(|| dependencies.get(&normalize_python_name(name)))
+            .cloned()
+            .ok_or_else(|| {
+                eyre!(
+                    "`{}` was not found in Cargo.lock, package-lock.json or poetry.lock",
+                    name
+                )
+            })
+    }
+
+    fn read_lockfiles(&self) -> Result<HashMap<String, Version>> {
+        let mut dependencies: HashMap<String, Version> = HashMap::new();
+        // Members of a workspace share the lockfile of the workspace root
+        let workspace_root = workspace::root(&self.root_directory, &|path| self.read(path));
+
+        for (file_name, read) in LOCKFILES {
+            let mut path = PathBuf::from(file_name);
+            let mut contents = self.read(&path);
+            if let Some(workspace_root) = workspace_root.as_ref().filter(|_| contents.is_none()) {
+                path = workspace_root.join(file_name);
+                contents = self.read(&path);
+            }
+            let contents = match contents {
+                Some(contents) => contents,
+                None => continue,
+            };
+
+            let packages =
+                read(&contents).ok_or_else(|| eyre!("Could not parse {}", path.display()))?;
+            for (name, version) in packages {
+                // Versions that are not semver (i.e. git dependencies) can't be compared
+                let version = match parse_version(&version) {
+                    Ok(version) => version,
+                    Err(_) => continue,
+                };
+                let entry = dependencies.entry(name).or_insert_with(|| version.clone());
+                if version > *entry {
+                    *entry = version;
+                }
+            }
+        }
+
+        Ok(dependencies)
+    }
+
+    fn read_version(&self) -> Result<Version> {
+        if let Some(file) = &self.version_source.file {
+            let contents = self
+                .read(file)
+                .ok_or_else(|| eyre!("could not read {}", file.display()))?;
+            let version = match &self.version_source.pattern {
+                Some(pattern) => find_in(&contents, pattern)?
+                    .ok_or_else(|| eyre!("{} does not match {}", file.display(), pattern))?,
+                None => contents.trim().to_string(),
+            };
+            return parse_version(&version);
+        }
+
+        if let Some(version) = self.cargo_package_field("version") {
+            return parse_version(&version);
+        }
+        for (file_name, read) in MANIFESTS {
+            let contents = match self.read(Path::new(file_name)) {
+                Some(contents) => contents,
+                None => continue,
+            };
+            if let Some(version) = read(&contents) {
+                return parse_version(&version);
+            }
+        }
+
+        Err(eyre!(
+            "the project version could not be found in Cargo.toml, package.json or pyproject.toml"
+        ))
+    }
+}
+
+/// Reads the version from the contents of a manifest
+type ManifestReader = fn(&str) -> Option<String>;
+
+/// Manifests besides Cargo.toml, which is read by `Project::cargo_package_field` as its fields can be
+/// inherited from the workspace
+const MANIFESTS: [(&str, ManifestReader); 2] = [
+    ("package.json", |contents| {
+        let manifest: serde_json::Value = serde_json::from_str(contents).ok()?;
+        manifest.get("version")?.as_str().map(String::from)
+    }),
+    ("pyproject.toml", |contents| {
+        let manifest: toml::Value = toml::from_str(contents).ok()?;
+        manifest
+            .get("project")
+            .or_else(|| manifest.get("tool")?.get("poetry"))?
+            .get("version")?
+            .as_str()
+            .map(String::from)
+    }),
+];
+
+/// Reads the names & versions of all resolved packages from the contents of a lockfile
+type LockfileReader = fn(&str) -> Option<Vec<(String, String)>>;
+
+const LOCKFILES: [(&str, LockfileReader); 3] = [
+    ("Cargo.lock", |contents| {
+        let lockfile: toml::Value = toml::from_str(contents).ok()?;
+        Some(toml_packages(&lockfile, |name| name.to_string()))
+    }),
+    ("package-lock.json", |contents| {
+        let lockfile: serde_json::Value = serde_json::from_str(contents).ok()?;
+        // Lockfile v2 & v3 list packages by path, i.e. `node_modules/a/node_modules/b`
+        let packages = lockfile
+            .get("packages")
+            .and_then(|packages| packages.as_object())
+            .map(|packages| {
+                packages
+                    .iter()
+                    .filter_map(|(path, package)| {
+                        let name = path.rsplit("node_modules/").next()?;
+                        let version = package.get("version")?.as_str()?;
+                        (!path.is_empty()).then(|| (name.to_string(), version.to_string()))
+                    })
+                    .collect::<Vec<_>>()
+            });
+        // Lockfile v1 lists packages by name
+        let dependencies = || {
+            let dependencies = lockfile.get("dependencies")?.as_object()?;
+            Some(
+                dependencies
+                    .iter()
+                    .filter_map(|(name, package)| {
+                        Some((name.clone(), package.get("version")?.as_str()?.to_string()))
+                    })
+                    .collect(),
+            )
+        };
+        packages.or_else(dependencies)
+    }),
+    ("poetry.lock", |contents| {
+        let lockfile: toml::Value = toml::from_str(contents).ok()?;
+        Some(toml_packages(&lockfile, normalize_python_name))
+    }),
+];
+
+/// Names & versions of the `[[package]]` tables of a TOML lockfile
+fn toml_packages(lockfile: &toml::Value, normalize: fn(&str) -> String) -> Vec<(String, String)> {
+    let packages = match lockfile
+        .get("package")
+        .and_then(|packages| packages.as_array())
+    {
+        Some(packages) => packages,
+        None => return vec![],
+    };
+
+    packages
+        .iter()
+        .filter_map(|package| {
+            let name = package.get("name")?.as_str()?;
+            let version = package.get("version")?.as_str()?;
+            Some((normalize(name), version.to_string()))
+        })
+        .collect()
+}
+
+/// Python package names are case-insensitive and treat `-`, `_` and `.` the same
+fn normalize_python_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Parses a version leniently, i.e. `2.0` as `2.0.0` and `v1.2.3` as `1.2.3`
+pub fn parse_version(version: &str) -> Result<Version> {
+    let version = version.trim().trim_start_matches('v');
+    let parts = version.split(['-', '+']).next().unwrap_or_default();
+    let padding = match parts.split('.').count() {
+        1 => ".0.0",
+        2 => ".0",
+        _ => "",
+    };
+    let padded = version.replacen(parts, &format!("{}{}", parts, padding), 1);
+
+    Version::parse(&padded).map_err(|_| eyre!("{} is not a valid version", version))
+}
+
+fn find_in(contents: &str, pattern: &str) -> Result<Option<String>> {
+    let matcher = RegexMatcher::new(pattern)?;
+    let mut captures = matcher.new_captures()?;
+    if !matcher.captures(contents.as_bytes(), &mut captures)? {
+        return Ok(None);
+    }
+
+    let range = matcher
+        .capture_index("version")
+        .and_then(|index| captures.get(index))
+        .or_else(|| captures.get(0));
+
+    Ok(range.map(|range| contents[range].to_string()))
+}
@@ -0,0 +1,142 @@
+use std::{fmt, path::Path};
+
+use eyre::Result;
+use globset::GlobMatcher;
+use serde::Deserialize;
+
+use crate::{paths, severity::SeverityPolicy};
+
+/// Overrides for TODOs in paths matching a glob, configured as `[[rules]]`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PathRule {
+    /// Glob relative to the root directory, i.e. `examples/**` - a trailing `/` matches everything in a directory
+    pub path: String,
+    /// Replaces the `[severity]` policy
+    pub severity: Option<SeverityPolicy>,
+    /// Replaces `--due-soon`
+    pub due_soon: Option<i64>,
+    /// Whether the TODOs can fail the build at all
+    pub fail: Option<bool>,
+    /// Tags (i.e. `#perf`) the TODOs may have - any others are a policy violation
+    pub allowed_tags: Option<Vec<String>>,
+    /// Whether the TODOs must name an owner (i.e. `@platform-team`) - TODOs without one are a policy violation
+    pub require_owner: Option<bool>,
+}
+
+/// The overrides that apply to a TODO - for each setting, the last matching rule that sets it wins
+#[derive(Debug, Clone, Default)]
+pub struct EffectiveRule {
+    /// Paths of the matching rules
+    pub paths: Vec<String>,
+    pub severity: Option<SeverityPolicy>,
+    pub due_soon: Option<i64>,
+    pub fail: Option<bool>,
+    pub allowed_tags: Option<Vec<String>>,
+    pub require_owner: Option<bool>,
+}
+
+impl fmt::Display for EffectiveRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut settings = vec![];
+        if let Some(severity) = &self.severity {
+            settings.push(format!(
+                "overdue: {}, malformed: {}",
+                severity.overdue, severity.malformed
+            ));
+            if !severity.escalation.is_empty() {
+                settings.push("escalated".to_string());
+            }
+        }
+        if let Some(due_soon) = self.due_soon {
+            settings.push(format!("due soon: {} days", due_soon));
+        }
+        if let Some(fail) = self.fail {
+            settings.push(format!("fail: {}", fail));
+        }
+        if let Some(allowed_tags) = &self.allowed_tags {
+            settings.push(format!("allowed tags: [{}]", allowed_tags.join(", ")));
+        }
+        if let Some(require_owner) = self.require_owner {
+            settings.push(format!("owner required: {}", require_owner));
+        }
+
+        write!(f, "{} ({})", self.paths.join(", "), settings.join(", "))
+    }
+}
+
+/// Compiled `[[rules]]`
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<(GlobMatcher, PathRule)>,
+}
+
+impl EffectiveRule {
+    /// Why a TODO breaks the rule, i.e. for a tag that is not allowed or a missing owner
+    pub fn violations(&self, tags: &[String], owner: Option<&str>) -> Vec<String> {
+        let mut violations = vec![];
+
+        if let Some(allowed_tags) = &self.allowed_tags {
+            for tag in tags {
+                if !allowed_tags.contains(tag) {
+                    violations.push(format!(
+                        "the tag #{} is not allowed in {} (allowed: {})",
+                        tag,
+                        self.paths.join(", "),
+                        allowed_tags.join(", ")
+                    ));
+                }
+            }
+        }
+        if self.require_owner == Some(true) && owner.is_none() {
+            violations.push(format!(
+                "an owner (i.e. `@team`) is required in {}",
+                self.paths.join(", ")
+            ));
+        }
+
+        violations
+    }
+}
+
+impl RuleSet {
+    pub fn new(rules: &[PathRule]) -> Result<RuleSet> {
+        let rules = rules
+            .iter()
+            .map(|rule| Ok((paths::glob(&rule.path)?.compile_matcher(), rule.clone())))
+            .collect::<Result<_>>()?;
+
+        Ok(RuleSet { rules })
+    }
+
+    /// The rule for a path relative to the root directory, or `None` if no rules match
+    pub fn for_path(&self, path: &Path) -> Option<EffectiveRule> {
+        let mut effective_rule: Option<EffectiveRule> = None;
+
+        for (glob, rule) in &self.rules {
+            if !glob.is_match(path) {
+                continue;
+            }
+
+            let effective_rule = effective_rule.get_or_insert_with(EffectiveRule::default);
+            effective_rule.paths.push(rule.path.clone());
+            if rule.severity.is_some() {
+                effective_rule.severity = rule.severity.clone();
+            }
+            if rule.due_soon.is_some() {
+                effective_rule.due_soon = rule.due_soon;
+            }
+            if rule.fail.is_some() {
+                effective_rule.fail = rule.fail;
+            }
+            if rule.allowed_tags.is_some() {
+                effective_rule.allowed_tags = rule.allowed_tags.clone();
+            }
+            if rule.require_owner.is_some() {
+                effective_rule.require_owner = rule.require_owner;
+            }
+        }
+
+        effective_rule
+    }
+}
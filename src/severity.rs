@@ -0,0 +1,84 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+/// How severe an overdue or malformed TODO is - only errors fail the build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn parse(text: &str) -> Option<Severity> {
+        match text.trim() {
+            "info" => Some(Severity::Info),
+            "warning" => Some(Severity::Warning),
+            "error" => Some(Severity::Error),
+            _ => None,
+        }
+    }
+
+    /// Splits a severity off the end of a TODO's date, i.e. `2024-01-01, warning`
+    pub fn split(date: &str) -> (&str, Option<Severity>) {
+        if let Some((date_part, severity)) = date.rsplit_once(',') {
+            if let Some(severity) = Severity::parse(severity) {
+                return (date_part.trim(), Some(severity));
+            }
+        }
+        (date, None)
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Which severity TODOs without one of their own get, configured under `[severity]`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SeverityPolicy {
+    /// Severity of overdue TODOs that no escalation rule applies to
+    pub overdue: Severity,
+    /// Severity of malformed TODOs
+    pub malformed: Severity,
+    /// Severities by how long TODOs have been overdue - the rule with the highest `after-days` applies
+    pub escalation: Vec<Escalation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Escalation {
+    /// Days since the deadline from which the rule applies
+    pub after_days: i64,
+    pub severity: Severity,
+}
+
+impl Default for SeverityPolicy {
+    fn default() -> Self {
+        SeverityPolicy {
+            overdue: Severity::Error,
+            malformed: Severity::Error,
+            escalation: vec![],
+        }
+    }
+}
+
+impl SeverityPolicy {
+    /// Severity of a TODO that is overdue since `days_overdue` days
+    pub fn overdue(&self, days_overdue: i64) -> Severity {
+        self.escalation
+            .iter()
+            .filter(|escalation| escalation.after_days <= days_overdue)
+            .max_by_key(|escalation| escalation.after_days)
+            .map_or(self.overdue, |escalation| escalation.severity)
+    }
+}
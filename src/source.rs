@@ -0,0 +1,448 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    types::Types,
+    Match,
+};
+
+use crate::{
+    archive::{self, Format},
+    attributes::GitAttributes,
+    core::{self, SearchOptions},
+    git,
+};
+
+/// Where the files to search for TODOs are read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceKind {
+    /// The files in the root directory
+    WorkingDirectory,
+    /// The files of a commit, i.e. `v1.4.0` or `HEAD~2`
+    Revision(String),
+    /// The staged contents of the files in the index
+    Index,
+    /// Only the listed files, as given by `--files-from` - ignore files don't apply to them
+    FileList(Vec<PathBuf>),
+    /// A single file read from stdin, reported by the given file name
+    Stdin(PathBuf),
+}
+
+/// Function run with the path of each file to report TODOs by, its path relative to the root directory
+/// and its contents
+pub type FileVisitor<'a> = dyn FnMut(&Path, &Path, Vec<u8>) -> Result<()> + 'a;
+
+/// Files to search for TODOs - each source applies ignore files itself
+pub trait Source {
+    /// Runs a function with each file to search
+    fn files_and(&mut self, f: &mut FileVisitor<'_>) -> Result<()>;
+
+    /// Nested repositories that were skipped, see `core::walk_files_and`
+    fn nested_repositories(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+}
+
+/// The source of the files in `root_directory`, as chosen by `options.source`
+///
+/// A root directory that is an archive is searched as one.
+pub fn open<'a>(root_directory: &Path, options: &'a SearchOptions) -> Box<dyn Source + 'a> {
+    let archive_format = Format::detect(root_directory).filter(|_| root_directory.is_file());
+
+    match &options.source {
+        SourceKind::WorkingDirectory if archive_format.is_some() => Box::new(Archive {
+            path: root_directory.into(),
+            format: archive_format.expect("Archive format was detected above"),
+            options,
+        }),
+        SourceKind::WorkingDirectory => Box::new(WorkingDirectory {
+            root_directory: root_directory.into(),
+            options,
+            nested_repositories: vec![],
+        }),
+        SourceKind::Revision(revision) => Box::new(GitTree {
+            root_directory: root_directory.into(),
+            revision: Some(revision.clone()),
+            options,
+        }),
+        SourceKind::Index => Box::new(GitTree {
+            root_directory: root_directory.into(),
+            revision: None,
+            options,
+        }),
+        SourceKind::FileList(paths) => Box::new(FileList {
+            root_directory: root_directory.into(),
+            paths: paths.clone(),
+        }),
+        SourceKind::Stdin(file_name) => Box::new(Stdin {
+            root_directory: root_directory.into(),
+            file_name: file_name.clone(),
+        }),
+    }
+}
+
+/// Reads a list of paths from a file, or stdin for `-` - one per line, or NUL-delimited if it contains a NUL
+pub fn read_file_list(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut contents = vec![];
+    if path == Path::new("-") {
+        std::io::stdin()
+            .read_to_end(&mut contents)
+            .wrap_err("Could not read the list of files from stdin")?;
+    } else {
+        contents = std::fs::read(path)
+            .wrap_err_with(|| format!("Could not read the list of files {}", path.display()))?;
+    }
+
+    let delimiter = if contents.contains(&b'\0') {
+        b'\0'
+    } else {
+        b'\n'
+    };
+    Ok(contents
+        .split(|byte| *byte == delimiter)
+        .map(|path| {
+            String::from_utf8_lossy(path)
+                .trim_end_matches('\r')
+                .to_string()
+        })
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Files in the working directory, as found by walking the root directory
+struct WorkingDirectory<'a> {
+    root_directory: PathBuf,
+    options: &'a SearchOptions,
+    nested_repositories: Vec<PathBuf>,
+}
+
+impl Source for WorkingDirectory<'_> {
+    fn files_and(&mut self, f: &mut FileVisitor<'_>) -> Result<()> {
+        let root_directory = &self.root_directory;
+        // The walker doesn't know about `.gitattributes`
+        let mut attributes = if self.options.no_ignore || self.options.no_gitattributes {
+            None
+        } else {
+            Some(GitAttributes::new(root_directory)?)
+        };
+
+        self.nested_repositories = core::walk_files_and(
+            |file| {
+                let relative_path = file
+                    .path()
+                    .strip_prefix(root_directory)
+                    .unwrap_or(file.path());
+
+                if file.metadata()?.is_file()
+                    && attributes
+                        .as_mut()
+                        .and_then(|attributes| attributes.skipped_by(relative_path))
+                        .is_none()
+                {
+                    f(file.path(), relative_path, std::fs::read(file.path())?)?;
+                }
+                Ok(())
+            },
+            root_directory.clone(),
+            self.options,
+        )?;
+
+        Ok(())
+    }
+
+    fn nested_repositories(&self) -> Vec<PathBuf> {
+        self.nested_repositories.clone()
+    }
+}
+
+/// Files of a commit or the index, read from the repository of the root directory
+struct GitTree<'a> {
+    root_directory: PathBuf,
+    /// `None` for the index
+    revision: Option<String>,
+    options: &'a SearchOptions,
+}
+
+impl Source for GitTree<'_> {
+    fn files_and(&mut self, f: &mut FileVisitor<'_>) -> Result<()> {
+        // Blobs are read in batches, so a large tree is never held in memory at once
+        const BATCH_SIZE: usize = 1024;
+
+        let entries = git::tree_entries(&self.root_directory, self.revision.as_deref())?;
+
+        let ignore_file_names = TreeFilter::ignore_file_names(self.options, true);
+        let ignore_files: Vec<&git::TreeEntry> = entries
+            .iter()
+            .filter(|entry| {
+                matches!(entry.path.file_name(), Some(file_name) if ignore_file_names.iter().any(|name| file_name == name.as_str()))
+            })
+            .collect();
+        let ignore_file_contents = git::read_blobs(
+            &self.root_directory,
+            &ignore_files
+                .iter()
+                .map(|entry| entry.object_id.clone())
+                .collect::<Vec<_>>(),
+        )?;
+        let filter = TreeFilter::new(
+            ignore_files
+                .iter()
+                .map(|entry| entry.path.clone())
+                .zip(ignore_file_contents)
+                .collect(),
+            self.options,
+            true,
+        )?;
+
+        // `.gitattributes` files are read from the tree as well, not from the working directory
+        let mut attributes = if self.options.no_ignore || self.options.no_gitattributes {
+            None
+        } else {
+            let attributes_files: Vec<&git::TreeEntry> = entries
+                .iter()
+                .filter(|entry| entry.path.file_name() == Some(".gitattributes".as_ref()))
+                .collect();
+            let attributes_file_contents = git::read_blobs(
+                &self.root_directory,
+                &attributes_files
+                    .iter()
+                    .map(|entry| entry.object_id.clone())
+                    .collect::<Vec<_>>(),
+            )?;
+            Some(GitAttributes::from_files(
+                &self.root_directory,
+                attributes_files
+                    .iter()
+                    .map(|entry| entry.path.clone())
+                    .zip(attributes_file_contents)
+                    .collect(),
+            ))
+        };
+
+        let entries: Vec<&git::TreeEntry> = entries
+            .iter()
+            .filter(|entry| {
+                filter.is_searched(&entry.path)
+                    && attributes
+                        .as_mut()
+                        .and_then(|attributes| attributes.skipped_by(&entry.path))
+                        .is_none()
+            })
+            .collect();
+        for batch in entries.chunks(BATCH_SIZE) {
+            let object_ids: Vec<String> =
+                batch.iter().map(|entry| entry.object_id.clone()).collect();
+            let blobs = git::read_blobs(&self.root_directory, &object_ids)?;
+
+            for (entry, contents) in batch.iter().zip(blobs) {
+                f(
+                    &self.root_directory.join(&entry.path),
+                    &entry.path,
+                    contents,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Members of an archive, read into memory
+struct Archive<'a> {
+    path: PathBuf,
+    format: Format,
+    options: &'a SearchOptions,
+}
+
+impl Source for Archive<'_> {
+    fn files_and(&mut self, f: &mut FileVisitor<'_>) -> Result<()> {
+        let members = archive::members(&self.path, self.format)?;
+
+        let ignore_file_names = TreeFilter::ignore_file_names(self.options, false);
+        let filter = TreeFilter::new(
+            members
+                .iter()
+                .filter(|member| {
+                    matches!(member.path.file_name(), Some(file_name) if ignore_file_names.iter().any(|name| file_name == name.as_str()))
+                })
+                .map(|member| (member.path.clone(), member.contents.clone()))
+                .collect(),
+            self.options,
+            false,
+        )?;
+
+        for member in members {
+            if filter.is_searched(&member.path) {
+                f(
+                    &archive::member_path(&self.path, &member.path),
+                    &member.path,
+                    member.contents,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Files listed explicitly, i.e. by an editor or a pre-commit hook
+struct FileList {
+    root_directory: PathBuf,
+    paths: Vec<PathBuf>,
+}
+
+impl Source for FileList {
+    fn files_and(&mut self, f: &mut FileVisitor<'_>) -> Result<()> {
+        for path in &self.paths {
+            let contents = std::fs::read(path)
+                .wrap_err_with(|| format!("Could not read {}", path.display()))?;
+            f(path, relative_path(&self.root_directory, path), contents)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Contents of a file piped to stdin, i.e. an unsaved editor buffer
+struct Stdin {
+    root_directory: PathBuf,
+    /// Path the contents are reported by - it doesn't need to exist
+    file_name: PathBuf,
+}
+
+impl Source for Stdin {
+    fn files_and(&mut self, f: &mut FileVisitor<'_>) -> Result<()> {
+        let mut contents = vec![];
+        std::io::stdin()
+            .read_to_end(&mut contents)
+            .wrap_err_with(|| format!("Could not read {} from stdin", self.file_name.display()))?;
+
+        f(
+            &self.file_name,
+            relative_path(&self.root_directory, &self.file_name),
+            contents,
+        )
+    }
+}
+
+/// Path of an explicitly given file relative to the root directory, or the path itself if it's outside of it
+fn relative_path<'a>(root_directory: &Path, path: &'a Path) -> &'a Path {
+    path.strip_prefix(root_directory).unwrap_or(path)
+}
+
+/// Applies the ignore files, hidden files, file types & maximum depth like the walker does,
+/// for files that are not read from the working directory
+pub struct TreeFilter {
+    /// Matchers of ignore files by the directory they are in - in the order they take precedence
+    ignores: Vec<(PathBuf, Gitignore)>,
+    /// Ignore files themselves are not searched
+    ignore_file_names: Vec<String>,
+    types: Option<Types>,
+    hidden: bool,
+    max_depth: Option<usize>,
+}
+
+impl TreeFilter {
+    /// Names of the ignore files that apply - custom ignore files first, as they take precedence
+    ///
+    /// Like for the walker, `.gitignore` files only apply to trees of a repository, not to archives.
+    pub fn ignore_file_names(options: &SearchOptions, in_repository: bool) -> Vec<String> {
+        let mut ignore_file_names = options.custom_ignore_file_names();
+        if !options.no_ignore {
+            ignore_file_names.push(".ignore".to_string());
+            if in_repository && !options.no_gitignore {
+                ignore_file_names.push(".gitignore".to_string());
+            }
+        }
+        ignore_file_names
+    }
+
+    /// Creates a filter from the ignore files of the tree, by their path relative to the root directory
+    pub fn new(
+        ignore_files: Vec<(PathBuf, Vec<u8>)>,
+        options: &SearchOptions,
+        in_repository: bool,
+    ) -> Result<TreeFilter> {
+        let ignore_file_names = TreeFilter::ignore_file_names(options, in_repository);
+
+        // Like the walker, custom ignore files take precedence over `.ignore` & `.gitignore` files -
+        // and ignore files of deeper directories over those of their parents
+        let mut ignores: Vec<(usize, usize, PathBuf, Gitignore)> = vec![];
+        for (path, contents) in ignore_files {
+            let rank = match path.file_name().and_then(|file_name| file_name.to_str()) {
+                Some(".ignore") => 1,
+                Some(".gitignore") => 2,
+                _ => 0,
+            };
+            let directory = path.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+
+            let mut builder = GitignoreBuilder::new(&directory);
+            for line in String::from_utf8_lossy(&contents).lines() {
+                builder.add_line(Some(path.clone()), line)?;
+            }
+            ignores.push((
+                rank,
+                directory.components().count(),
+                directory,
+                builder.build()?,
+            ));
+        }
+        ignores.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+        let types = options.types()?;
+
+        Ok(TreeFilter {
+            ignores: ignores
+                .into_iter()
+                .map(|(_, _, directory, gitignore)| (directory, gitignore))
+                .collect(),
+            ignore_file_names,
+            types,
+            hidden: options.hidden || options.no_ignore,
+            max_depth: options.max_depth,
+        })
+    }
+
+    /// Whether a file, by its path relative to the root directory, is searched
+    pub fn is_searched(&self, path: &Path) -> bool {
+        let file_name = path.file_name().unwrap_or_default();
+        if self
+            .ignore_file_names
+            .iter()
+            .any(|name| file_name == name.as_str())
+        {
+            return false;
+        }
+        if !self.hidden
+            && path
+                .iter()
+                .any(|component| component.to_string_lossy().starts_with('.'))
+        {
+            return false;
+        }
+        if matches!(self.max_depth, Some(max_depth) if path.iter().count() > max_depth) {
+            return false;
+        }
+        if matches!(&self.types, Some(types) if types.matched(path, false).is_ignore()) {
+            return false;
+        }
+
+        for (directory, gitignore) in &self.ignores {
+            if !path.starts_with(directory) {
+                continue;
+            }
+            match gitignore.matched_path_or_any_parents(path, false) {
+                Match::Ignore(_) => return false,
+                Match::Whitelist(_) => return true,
+                Match::None => (),
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use grep::{
+    matcher::{Captures, Matcher},
+    regex::RegexMatcher,
+};
+use serde::Deserialize;
+
+use crate::language::Comment;
+
+/// Matches inline suppressions, i.e. an `ignore-line` directive with a reason after `--`
+const PATTERN: &str = r"todo-ci:\s*(?P<directive>ignore-next-line|ignore-line|disable|enable)\b(?:\s*--\s*(?P<reason>.*))?";
+
+/// How inline suppressions are checked, configured under `[suppressions]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SuppressionPolicy {
+    /// Suppressions without a reason after `--` don't suppress anything
+    pub require_reason: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+    /// Suppresses TODOs on the following line
+    IgnoreNextLine,
+    /// Suppresses TODOs on the same line
+    IgnoreLine,
+    /// Suppresses TODOs until the next `enable`, or the end of the file
+    Disable,
+    /// Ends a `disable` region
+    Enable,
+}
+
+impl Directive {
+    fn parse(text: &str) -> Option<Directive> {
+        match text {
+            "ignore-next-line" => Some(Directive::IgnoreNextLine),
+            "ignore-line" => Some(Directive::IgnoreLine),
+            "disable" => Some(Directive::Disable),
+            "enable" => Some(Directive::Enable),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Directive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Directive::IgnoreNextLine => write!(f, "ignore-next-line"),
+            Directive::IgnoreLine => write!(f, "ignore-line"),
+            Directive::Disable => write!(f, "disable"),
+            Directive::Enable => write!(f, "enable"),
+        }
+    }
+}
+
+/// An inline `todo-ci:` suppression comment
+#[derive(Debug, Clone)]
+pub struct Suppression {
+    pub file: PathBuf,
+    pub line_number: i32,
+    pub directive: Directive,
+    pub reason: Option<String>,
+    /// Lines the suppression applies to, empty for `enable`
+    pub lines: Range<i32>,
+    /// Whether it suppressed a TODO - or for `enable`, ended a `disable` region
+    pub used: bool,
+    /// Whether it is missing a required reason, in which case it doesn't suppress anything
+    pub missing_reason: bool,
+}
+
+impl fmt::Display for Suppression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`todo-ci: {}` on {}:{}",
+            self.directive,
+            self.file.display(),
+            self.line_number
+        )?;
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the suppressions of a file - only inside of comments if `comments` are known
+pub fn find(
+    file_path: &Path,
+    lines: &[&str],
+    comments: Option<&[Vec<Comment>]>,
+    policy: &SuppressionPolicy,
+) -> Vec<Suppression> {
+    let matcher = RegexMatcher::new(PATTERN).expect("Regex should be valid");
+    let mut captures = matcher.new_captures().expect("Regex should be valid");
+    let mut suppressions: Vec<Suppression> = vec![];
+    let end_of_file = lines.len() as i32 + 1;
+
+    for (index, line) in lines.iter().enumerate() {
+        let line_number = index as i32 + 1;
+        let segments: Vec<Range<usize>> = match comments {
+            Some(comments) => comments[index]
+                .iter()
+                .map(|comment| comment.range.clone())
+                .collect(),
+            None => vec![Range {
+                start: 0,
+                end: line.len(),
+            }],
+        };
+
+        for segment in segments {
+            let text = &line[segment];
+            if !matcher
+                .captures(text.as_bytes(), &mut captures)
+                .expect("Regex should be valid")
+            {
+                continue;
+            }
+
+            // Unwraps here are ok - the directive group always participates in a match
+            let directive = Directive::parse(&text[captures.get(1).unwrap()]).unwrap();
+            let reason = captures
+                .get(2)
+                .map(|reason| {
+                    text[reason]
+                        .trim()
+                        .trim_end_matches("*/")
+                        .trim()
+                        // Suppressions quoted in Markdown or doc comments
+                        .trim_end_matches('`')
+                        .to_string()
+                })
+                .filter(|reason| !reason.is_empty());
+            let lines = match directive {
+                Directive::IgnoreNextLine => line_number + 1..line_number + 2,
+                Directive::IgnoreLine => line_number..line_number + 1,
+                Directive::Disable => line_number + 1..end_of_file,
+                Directive::Enable => line_number..line_number,
+            };
+
+            // Ends the last open `disable` region
+            let mut used = false;
+            if directive == Directive::Enable {
+                if let Some(disable) = suppressions.iter_mut().rev().find(|suppression| {
+                    suppression.directive == Directive::Disable
+                        && suppression.lines.end == end_of_file
+                }) {
+                    disable.lines.end = line_number;
+                    used = true;
+                }
+            }
+
+            suppressions.push(Suppression {
+                file: file_path.into(),
+                line_number,
+                missing_reason: policy.require_reason
+                    && directive != Directive::Enable
+                    && reason.is_none(),
+                directive,
+                reason,
+                lines,
+                used,
+            });
+            break;
+        }
+    }
+
+    suppressions
+}
+
+/// The suppression of a TODO starting on `line_number`, marking it as used
+pub fn apply(suppressions: &mut [Suppression], line_number: i32) -> Option<Suppression> {
+    let suppression = suppressions.iter_mut().find(|suppression| {
+        !suppression.missing_reason && suppression.lines.contains(&line_number)
+    })?;
+    suppression.used = true;
+    Some(suppression.clone())
+}
@@ -0,0 +1,272 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Result, WrapErr};
+use globset::Glob;
+
+/// Directories that are never walked into when expanding `**` in member globs
+const SKIPPED_DIRECTORIES: &[&str] = &["node_modules", "target"];
+
+/// Packages of the Cargo, npm or pnpm workspace in the root directory
+///
+/// The root directory always comes first, even without a `[package]` of its own, so that files which
+/// belong to no member (i.e. `scripts/` or the README of a virtual workspace) are searched as well.
+pub fn packages(root_directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut found_workspace = false;
+    let mut packages = vec![];
+
+    let cargo_manifest = root_directory.join("Cargo.toml");
+    if cargo_manifest.is_file() {
+        let manifest: toml::Value = toml::from_str(&read(&cargo_manifest)?)
+            .wrap_err_with(|| format!("Could not parse {}", cargo_manifest.display()))?;
+
+        if let Some(workspace) = manifest.get("workspace") {
+            found_workspace = true;
+            packages.append(&mut members(
+                root_directory,
+                &toml_strings(workspace.get("members")),
+                &toml_strings(workspace.get("exclude")),
+                "Cargo.toml",
+            )?);
+        }
+    }
+
+    let npm_manifest = root_directory.join("package.json");
+    if npm_manifest.is_file() {
+        let manifest: serde_json::Value = serde_json::from_str(&read(&npm_manifest)?)
+            .wrap_err_with(|| format!("Could not parse {}", npm_manifest.display()))?;
+
+        // Either a list of globs, or an object with them under `packages`
+        let workspaces = manifest.get("workspaces").map(|workspaces| {
+            workspaces
+                .get("packages")
+                .unwrap_or(workspaces)
+                .as_array()
+                .map(|patterns| {
+                    patterns
+                        .iter()
+                        .filter_map(|pattern| pattern.as_str().map(String::from))
+                        .collect::<Vec<String>>()
+                })
+                .unwrap_or_default()
+        });
+        if let Some(patterns) = workspaces {
+            found_workspace = true;
+            packages.append(&mut npm_members(root_directory, &patterns)?);
+        }
+    }
+
+    let pnpm_manifest = root_directory.join("pnpm-workspace.yaml");
+    if pnpm_manifest.is_file() {
+        found_workspace = true;
+        let patterns = pnpm_packages(&read(&pnpm_manifest)?);
+        packages.append(&mut npm_members(root_directory, &patterns)?);
+    }
+
+    if !found_workspace {
+        return Err(eyre!(
+            "No Cargo, npm or pnpm workspace found in {}",
+            root_directory.display()
+        ));
+    }
+
+    packages.retain(|package| package.as_path() != root_directory);
+    packages.sort();
+    packages.dedup();
+    packages.insert(0, root_directory.to_path_buf());
+
+    Ok(packages)
+}
+
+/// The root directory of the Cargo, npm or pnpm workspace `directory` is in - the closest directory,
+/// itself included, that declares a workspace
+///
+/// Files are read with `read` by their path relative to `directory`, so that they can come from a tree
+/// instead of the working directory, and the root is returned relative to `directory` as well.
+pub fn root(directory: &Path, read: &dyn Fn(&Path) -> Option<String>) -> Option<PathBuf> {
+    closest(directory, |ancestor| {
+        is_cargo_root(read, ancestor)
+            || is_npm_root(read, ancestor)
+            || read(&ancestor.join("pnpm-workspace.yaml")).is_some()
+    })
+}
+
+/// The root directory of the Cargo workspace `directory` is in, see `root`
+pub fn cargo_root(directory: &Path, read: &dyn Fn(&Path) -> Option<String>) -> Option<PathBuf> {
+    closest(directory, |ancestor| is_cargo_root(read, ancestor))
+}
+
+/// The closest of `directory`, `..`, `../..` and so on that `is_root`
+fn closest(directory: &Path, is_root: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    let depth = directory.canonicalize().ok()?.ancestors().count();
+    (0..depth)
+        .map(|up| (0..up).map(|_| "..").collect::<PathBuf>())
+        .find(|ancestor| is_root(ancestor))
+}
+
+fn is_cargo_root(read: &dyn Fn(&Path) -> Option<String>, directory: &Path) -> bool {
+    matches!(
+        read(&directory.join("Cargo.toml")).map(|contents| toml::from_str::<toml::Value>(&contents)),
+        Some(Ok(manifest)) if manifest.get("workspace").is_some()
+    )
+}
+
+fn is_npm_root(read: &dyn Fn(&Path) -> Option<String>, directory: &Path) -> bool {
+    matches!(
+        read(&directory.join("package.json")).map(|contents| serde_json::from_str::<serde_json::Value>(&contents)),
+        Some(Ok(manifest)) if manifest.get("workspaces").is_some()
+    )
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).wrap_err_with(|| format!("Could not read {}", path.display()))
+}
+
+fn toml_strings(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|value| value.as_array())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Members of an npm or pnpm workspace, where globs starting with `!` exclude packages
+fn npm_members(root_directory: &Path, patterns: &[String]) -> Result<Vec<PathBuf>> {
+    let (exclude, include): (Vec<String>, Vec<String>) = patterns
+        .iter()
+        .cloned()
+        .partition(|pattern| pattern.starts_with('!'));
+    let exclude: Vec<String> = exclude
+        .iter()
+        .map(|pattern| pattern.trim_start_matches('!').to_string())
+        .collect();
+
+    members(root_directory, &include, &exclude, "package.json")
+}
+
+/// Reads the globs of the `packages` list of a `pnpm-workspace.yaml` file
+fn pnpm_packages(contents: &str) -> Vec<String> {
+    let mut patterns = vec![];
+    let mut in_packages = false;
+
+    for line in contents.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) && !line.starts_with('-') {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        if let Some(pattern) = line.trim().strip_prefix('-').filter(|_| in_packages) {
+            patterns.push(
+                pattern
+                    .trim()
+                    .trim_matches(|c| c == '\'' || c == '"')
+                    .to_string(),
+            );
+        }
+    }
+
+    patterns
+}
+
+/// Directories matching the member globs, but none of the excluded ones, that contain a manifest
+fn members(
+    root_directory: &Path,
+    include: &[String],
+    exclude: &[String],
+    manifest: &str,
+) -> Result<Vec<PathBuf>> {
+    let mut excluded = vec![];
+    for pattern in exclude {
+        excluded.append(&mut expand(root_directory, pattern)?);
+    }
+
+    let mut members = vec![];
+    for pattern in include {
+        members.append(
+            &mut expand(root_directory, pattern)?
+                .into_iter()
+                .filter(|member| member.join(manifest).is_file() && !excluded.contains(member))
+                .collect(),
+        );
+    }
+
+    Ok(members)
+}
+
+/// Directories matching a glob relative to the root directory, i.e. `crates/*` or `packages/**`
+fn expand(root_directory: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let mut directories = vec![root_directory.to_path_buf()];
+
+    for component in pattern
+        .trim_start_matches("./")
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+    {
+        directories = if component == "**" {
+            directories
+                .iter()
+                .flat_map(|directory| {
+                    let mut descendants = vec![directory.clone()];
+                    descendants.append(&mut all_subdirectories(directory));
+                    descendants
+                })
+                .collect()
+        } else if component.contains(&['*', '?', '[', '{'][..]) {
+            let matcher = Glob::new(component)
+                .wrap_err_with(|| format!("Invalid workspace member glob {}", pattern))?
+                .compile_matcher();
+            directories
+                .iter()
+                .flat_map(subdirectories)
+                .filter(|directory| {
+                    matches!(directory.file_name(), Some(file_name) if matcher.is_match(file_name))
+                })
+                .collect()
+        } else {
+            directories
+                .iter()
+                .map(|directory| directory.join(component))
+                .filter(|directory| directory.is_dir())
+                .collect()
+        };
+    }
+
+    Ok(directories)
+}
+
+/// Subdirectories of a directory, without hidden ones
+fn subdirectories(directory: &PathBuf) -> Vec<PathBuf> {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut subdirectories: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| matches!(entry.file_type(), Ok(file_type) if file_type.is_dir()))
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+        .collect();
+    subdirectories.sort();
+    subdirectories
+}
+
+/// Subdirectories of a directory at any depth, without hidden or dependency directories
+fn all_subdirectories(directory: &PathBuf) -> Vec<PathBuf> {
+    subdirectories(directory)
+        .into_iter()
+        .filter(|subdirectory| {
+            !matches!(subdirectory.file_name(), Some(file_name) if SKIPPED_DIRECTORIES.iter().any(|name| file_name == *name))
+        })
+        .flat_map(|subdirectory| {
+            let mut descendants = all_subdirectories(&subdirectory);
+            descendants.insert(0, subdirectory);
+            descendants
+        })
+        .collect()
+}
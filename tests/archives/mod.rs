@@ -0,0 +1,128 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use flate2::{write::GzEncoder, Compression};
+use pretty_assertions::assert_eq;
+use todo_ci::core::{SearchOptions, SearchResult};
+
+const MEMBERS: &[(&str, &str)] = &[
+    (
+        "pkg-1.0.0/src/lib.rs",
+        "// @todo(2000-01-01): Overdue TODO in the library\n",
+    ),
+    (
+        "pkg-1.0.0/tests/test.rs",
+        "// @todo(2222-01-01): TODO in a test\n",
+    ),
+    (
+        "pkg-1.0.0/generated.rs",
+        "// @todo(2222-01-01): Ignored TODO\n",
+    ),
+    ("pkg-1.0.0/.tdignore", "generated.rs\n"),
+    // Like in an extracted directory, `.gitignore` files don't apply outside of a repository
+    ("pkg-1.0.0/.gitignore", "tests/\n"),
+];
+
+/// Creates a gzipped tarball, like a `.crate` file
+fn tar_gz(path: &Path) {
+    let mut builder = tar::Builder::new(GzEncoder::new(
+        File::create(path).unwrap(),
+        Compression::default(),
+    ));
+    for (name, contents) in MEMBERS {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, contents.as_bytes())
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+fn zip(path: &Path) {
+    let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+    for (name, contents) in MEMBERS {
+        writer
+            .start_file(*name, zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(contents.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+fn search(archive: &Path, options: SearchOptions) -> SearchResult {
+    todo_ci::core::search(archive.to_path_buf(), &options).unwrap()
+}
+
+fn files(search_results: &SearchResult) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = search_results
+        .todos
+        .iter()
+        .map(|todo| todo.file.clone())
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn find_todos_in_a_crate() {
+    let directory = tempfile::tempdir().unwrap();
+    let archive = directory.path().join("pkg-1.0.0.crate");
+    tar_gz(&archive);
+
+    let search_results = search(&archive, SearchOptions::default());
+
+    assert_eq!(
+        files(&search_results),
+        vec![
+            PathBuf::from(format!("{}!/pkg-1.0.0/src/lib.rs", archive.display())),
+            PathBuf::from(format!("{}!/pkg-1.0.0/tests/test.rs", archive.display())),
+        ]
+    );
+    assert_eq!(search_results.statistics.files_searched, 2);
+    assert_eq!(search_results.statistics.overdue_todo_count, 1);
+}
+
+#[test]
+fn find_todos_in_a_zip_archive_with_globs() {
+    let directory = tempfile::tempdir().unwrap();
+    let archive = directory.path().join("release.zip");
+    zip(&archive);
+
+    let search_results = search(
+        &archive,
+        SearchOptions {
+            exclude: vec!["**/tests/".to_string()],
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        files(&search_results),
+        vec![PathBuf::from(format!(
+            "{}!/pkg-1.0.0/src/lib.rs",
+            archive.display()
+        ))]
+    );
+}
+
+#[test]
+fn test_todos_in_archives_in_output() {
+    let directory = tempfile::tempdir().unwrap();
+    tar_gz(&directory.path().join("release.tar.gz"));
+
+    assert_cmd::Command::cargo_bin("todo-ci")
+        .unwrap()
+        .arg("release.tar.gz")
+        .current_dir(directory.path())
+        .assert()
+        .code(todo_ci::exit_code::OVERDUE)
+        .stdout(predicates::str::contains(
+            "release.tar.gz!/pkg-1.0.0/src/lib.rs:1",
+        ));
+}
@@ -0,0 +1,6 @@
+
+def main():
+    """
+    @todo(2221-07-10): Docstring TODO on line 4
+    """
+    print("@todo(1991-07-10): Not a TODO as it is inside of a string")  # @todo(2221-07-10): Hash comment TODO on line 6
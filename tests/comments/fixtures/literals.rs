@@ -0,0 +1,9 @@
+fn main() {
+    let quote = '"'; // @todo(2222-01-01): TODO after a char literal on line 2
+    let label: &'static str = "'"; // @todo(2222-01-01): TODO after a lifetime on line 3
+    let raw = r#"raw " // @todo(2000-01-01): In a raw string"#;
+    let bytes = br"bytes // @todo(2000-01-01): In a raw byte string";
+    let generated = "first line
+// @todo(2000-01-01): In a multi-line string
+";
+}
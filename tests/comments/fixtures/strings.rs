@@ -0,0 +1,9 @@
+
+fn main() {
+    let _generated = "// @todo(1991-07-10): Not a TODO as it is inside of a string";
+    println!("@todo(1991-07-10): {}", "Not a TODO either"); // @todo(2221-07-10): Line comment TODO on line 4
+    /* @todo(2221-07-10): Block comment TODO on line 5 */
+    /*
+     * @todo(2221-07-10): Multi-line block comment TODO on line 7
+     */
+}
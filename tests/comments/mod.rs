@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use todo_ci::core::SearchOptions;
+
+#[test]
+fn find_todos_only_inside_of_comments() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/comments/fixtures").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.files_searched, 5);
+    assert_eq!(search_results.statistics.overdue_todo_count, 0);
+    assert_eq!(search_results.statistics.valid_todo_count, 9);
+
+    let mut found: Vec<(i32, &str)> = search_results
+        .todos
+        .iter()
+        .map(|todo| (todo.line_number, todo.description.as_str()))
+        .collect();
+    found.sort();
+
+    assert_eq!(
+        found,
+        vec![
+            (2, "TODO after a char literal on line 2"),
+            (3, "TODO after a lifetime on line 3"),
+            (4, "CSS comment TODO on line 4"),
+            (4, "Docstring TODO on line 4"),
+            (4, "Line comment TODO on line 4"),
+            (5, "Block comment TODO on line 5"),
+            (5, "HTML comment TODO on line 5"),
+            (6, "Hash comment TODO on line 6"),
+            (7, "Multi-line block comment TODO on line 7"),
+        ]
+    );
+}
+
+#[test]
+fn find_todos_anywhere() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/comments/fixtures").to_path_buf(),
+        &SearchOptions {
+            anywhere: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Strings and prose mentioning the syntax are matched as well
+    assert_eq!(search_results.statistics.overdue_todo_count, 8);
+}
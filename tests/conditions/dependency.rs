@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use todo_ci::core::SearchOptions;
+
+#[test]
+fn find_todos_with_dependency_conditions() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/conditions/fixtures/dependency").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.overdue_todo_count, 2);
+    assert_eq!(search_results.statistics.valid_todo_count, 2);
+    assert_eq!(search_results.statistics.malformed_todo_count, 1);
+
+    let conditions: Vec<(&str, &str, bool)> = search_results
+        .todos
+        .iter()
+        .filter_map(|todo| todo.condition.as_ref())
+        .map(|condition| {
+            (
+                condition.text.as_str(),
+                condition.current.as_str(),
+                condition.met,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        conditions,
+        vec![
+            ("dep:serde>=2.0", "2.0.1", true),
+            ("dep:chrono>=0.5", "0.4.22", false),
+            ("dep:left-pad>=1.3", "1.3.0", true),
+            ("dep:typing-extensions>=5", "4.8.0", false),
+        ]
+    );
+
+    assert_eq!(
+        search_results.todos[4].description,
+        "`dep:missing>=1.0` could not be checked: `missing` was not found in Cargo.lock, package-lock.json or poetry.lock."
+    );
+}
@@ -0,0 +1,8 @@
+
+fn main() {
+    // @todo(dep:serde>=2.0): Overdue Cargo.lock TODO on line 3
+    // @todo(dep:chrono>=0.5): Valid Cargo.lock TODO on line 4
+    // @todo(dep:left-pad>=1.3): Overdue package-lock.json TODO on line 5
+    // @todo(dep:typing-extensions>=5): Valid poetry.lock TODO on line 6
+    // @todo(dep:missing>=1.0): Malformed TODO on line 7
+}
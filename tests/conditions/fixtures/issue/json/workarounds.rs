@@ -0,0 +1,6 @@
+
+pub fn shim() {
+    // @todo(issue:#1): Overdue issue TODO on line 3
+    // @todo(issue:#2): Valid issue TODO on line 4
+    // @todo(issue:#3): Malformed issue TODO on line 5
+}
@@ -0,0 +1,5 @@
+// @todo(when-missing:legacy/): Valid path TODO on line 1
+// @todo(when-exists:src/new_api.rs): Overdue path TODO on line 2
+// @todo(when-exists:src/newer_api.rs): Valid path TODO on line 3
+// @todo(when-missing:src/removed.rs): Overdue path TODO on line 4
+// @todo(when-exists:): Malformed path TODO on line 5
@@ -0,0 +1,5 @@
+
+pub fn shim() {
+    // @todo(rust>=1.65): Overdue MSRV TODO on line 3
+    // @todo(rust>=1.80): Valid MSRV TODO on line 4
+}
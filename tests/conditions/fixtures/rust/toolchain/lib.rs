@@ -0,0 +1,4 @@
+
+pub fn shim() {
+    // @todo(rust>=1.65): Valid toolchain TODO on line 3
+}
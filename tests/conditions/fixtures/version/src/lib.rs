@@ -0,0 +1,6 @@
+
+pub fn legacy_api() {
+    // @todo(version>=1.0.0): Overdue version TODO on line 3
+    // @todo(version>=2.0.0): Valid version TODO on line 4
+    // @todo(version>=two): Malformed version TODO on line 5
+}
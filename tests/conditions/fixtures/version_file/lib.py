@@ -0,0 +1,4 @@
+
+def legacy_api():
+    # @todo(version>=2.0.0): Overdue version TODO on line 3
+    pass
@@ -0,0 +1,140 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use pretty_assertions::assert_eq;
+use todo_ci::{
+    core::{SearchOptions, SearchResult},
+    issue::IssueSource,
+};
+
+fn search(root_directory: &Path, issue_source: IssueSource) -> SearchResult {
+    todo_ci::core::search(
+        root_directory.to_path_buf(),
+        &SearchOptions {
+            issue_source,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn assert_counts(search_results: &SearchResult, valid: i32, overdue: i32, malformed: i32) {
+    assert_eq!(search_results.statistics.valid_todo_count, valid);
+    assert_eq!(search_results.statistics.overdue_todo_count, overdue);
+    assert_eq!(search_results.statistics.malformed_todo_count, malformed);
+}
+
+#[test]
+fn find_todos_with_issue_conditions_from_json_export() {
+    let search_results = search(
+        Path::new("./tests/conditions/fixtures/issue/json"),
+        IssueSource {
+            file: Some("issues.json".into()),
+            ..Default::default()
+        },
+    );
+
+    assert_counts(&search_results, 1, 1, 1);
+}
+
+#[test]
+fn find_todos_with_issue_conditions_from_csv_export() {
+    let search_results = search(
+        Path::new("./tests/conditions/fixtures/issue/csv"),
+        IssueSource {
+            file: Some("issues.csv".into()),
+            ..Default::default()
+        },
+    );
+
+    assert_counts(&search_results, 1, 1, 1);
+}
+
+/// Serves `count` requests for `/issues/<number>` in the GitLab shape, counting the requests served
+fn serve_issues(count: usize) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/issues", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let served_requests = requests.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().take(count) {
+            served_requests.fetch_add(1, Ordering::SeqCst);
+            let mut stream = stream.unwrap();
+            let mut request_line = String::new();
+            BufReader::new(&stream)
+                .read_line(&mut request_line)
+                .unwrap();
+
+            let (status, body) = if request_line.starts_with("GET /issues/1 ") {
+                ("200 OK", r#"{ "iid": 1, "state": "closed" }"#)
+            } else if request_line.starts_with("GET /issues/2 ") {
+                ("200 OK", r#"{ "iid": 2, "state": "opened" }"#)
+            } else {
+                ("404 Not Found", r#"{ "message": "404 Not found" }"#)
+            };
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+#[test]
+fn find_todos_with_issue_conditions_from_api_with_offline_fallback() {
+    let root_directory = tempfile::tempdir().unwrap();
+    std::fs::copy(
+        "./tests/conditions/fixtures/issue/json/workarounds.rs",
+        root_directory.path().join("workarounds.rs"),
+    )
+    .unwrap();
+
+    let issue_source = IssueSource {
+        url: Some(serve_issues(3).0),
+        cache_file: Some("issues-cache.json".into()),
+        ..Default::default()
+    };
+    let search_results = search(root_directory.path(), issue_source.clone());
+    assert_counts(&search_results, 1, 1, 1);
+
+    // The server is gone, so the cached states are used
+    let search_results = search(root_directory.path(), issue_source);
+    assert_counts(&search_results, 1, 1, 1);
+}
+
+#[test]
+fn fetch_each_issue_once() {
+    let root_directory = tempfile::tempdir().unwrap();
+    std::fs::write(
+        root_directory.path().join("workarounds.rs"),
+        "// @todo(issue:#1): First TODO waiting on #1\n// @todo(issue:#1): Second TODO waiting on #1\n",
+    )
+    .unwrap();
+
+    let (url, requests) = serve_issues(10);
+    let issue_source = IssueSource {
+        url: Some(url),
+        cache_file: Some("issues-cache.json".into()),
+        ..Default::default()
+    };
+    let search_results = search(root_directory.path(), issue_source);
+
+    assert_counts(&search_results, 0, 2, 0);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+    assert!(root_directory.path().join("issues-cache.json").is_file());
+}
@@ -0,0 +1,5 @@
+mod dependency;
+mod issue;
+mod path;
+mod rust;
+mod version;
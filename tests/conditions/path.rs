@@ -0,0 +1,25 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use todo_ci::core::{SearchOptions, TodoState};
+
+#[test]
+fn find_todos_with_path_conditions() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/conditions/fixtures/path").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.valid_todo_count, 2);
+    assert_eq!(search_results.statistics.overdue_todo_count, 2);
+    assert_eq!(search_results.statistics.malformed_todo_count, 1);
+
+    let mut todos = search_results.todos;
+    todos.sort_by_key(|todo| todo.line_number);
+
+    assert!(matches!(todos[0].state, TodoState::Valid));
+    assert_eq!(todos[0].condition.as_ref().unwrap().current, "exists");
+    assert!(matches!(todos[3].state, TodoState::Overdue));
+    assert_eq!(todos[3].condition.as_ref().unwrap().current, "missing");
+}
@@ -0,0 +1,35 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use todo_ci::core::SearchOptions;
+
+#[test]
+fn find_todos_with_rust_version_conditions() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/conditions/fixtures/rust/msrv").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.overdue_todo_count, 1);
+    assert_eq!(search_results.statistics.valid_todo_count, 1);
+    assert_eq!(
+        search_results.todos[0].condition.as_ref().unwrap().current,
+        "1.75.0"
+    );
+}
+
+#[test]
+fn find_todos_with_rust_version_conditions_using_the_toolchain_file() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/conditions/fixtures/rust/toolchain").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.valid_todo_count, 1);
+    assert_eq!(
+        search_results.todos[0].condition.as_ref().unwrap().current,
+        "1.64.0"
+    );
+}
@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+use todo_ci::{
+    core::{SearchOptions, TodoState},
+    project::VersionSource,
+};
+
+#[test]
+fn find_todos_with_version_conditions() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/conditions/fixtures/version").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.overdue_todo_count, 1);
+    assert_eq!(search_results.statistics.valid_todo_count, 1);
+    assert_eq!(search_results.statistics.malformed_todo_count, 1);
+
+    let overdue = &search_results.todos[0];
+    assert!(matches!(overdue.state, TodoState::Overdue));
+    assert_eq!(overdue.deadline, None);
+
+    let condition = overdue.condition.as_ref().unwrap();
+    assert_eq!(condition.text, "version>=1.0.0");
+    assert_eq!(condition.current, "1.4.0");
+
+    assert_eq!(
+        search_results.todos[2].description,
+        "`version>=two` could not be checked: `>=two` is not a valid version requirement."
+    );
+}
+
+#[test]
+fn find_todos_with_version_conditions_using_a_version_file() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/conditions/fixtures/version_file").to_path_buf(),
+        &SearchOptions {
+            version_source: VersionSource {
+                file: Some(PathBuf::from("VERSION.txt")),
+                pattern: Some(r"release: (?P<version>\S+)".to_string()),
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.overdue_todo_count, 1);
+    assert_eq!(
+        search_results.todos[0].condition.as_ref().unwrap().current,
+        "2.1.0"
+    );
+}
@@ -0,0 +1,11 @@
+
+fn main() {
+    // @todo(2221-07): Month TODO on line 3
+    // @todo(2221-Q3): Quarter TODO on line 4
+    // @todo(2221-W32): Week TODO on line 5
+    // @todo(2221-07-10T17:00): Date & time TODO on line 6
+    // @todo(10/07/2221): Custom format TODO on line 7
+    // @todo(1991-Q3): Expired quarter TODO on line 8
+    // @todo(1991-07-10T17:00): Expired date & time TODO on line 9
+    println!("Hello World!");
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use assert_cmd::Command;
+use chrono::NaiveDate;
+use pretty_assertions::assert_eq;
+use todo_ci::{core::SearchOptions, deadline::Deadline};
+
+#[test]
+fn find_todos_with_coarse_and_custom_deadlines() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/deadlines/fixtures").to_path_buf(),
+        &SearchOptions {
+            date_formats: vec!["%d/%m/%Y".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.valid_todo_count, 5);
+    assert_eq!(search_results.statistics.overdue_todo_count, 2);
+    assert_eq!(search_results.statistics.malformed_todo_count, 0);
+
+    let deadlines: Vec<String> = search_results
+        .todos
+        .iter()
+        .map(|todo| todo.deadline.as_ref().unwrap().to_string())
+        .collect();
+
+    assert_eq!(
+        deadlines,
+        vec![
+            "2221-07",
+            "2221-Q3",
+            "2221-W32",
+            "2221-07-10T17:00",
+            "2221-07-10",
+            "1991-Q3",
+            "1991-07-10T17:00"
+        ]
+    );
+}
+
+#[test]
+fn coarse_deadlines_are_due_at_the_end_of_their_period() {
+    let deadline = |text: &str| Deadline::parse(text, &[]).unwrap().last_day();
+
+    assert_eq!(deadline("2024-02"), NaiveDate::from_ymd(2024, 2, 29));
+    assert_eq!(deadline("2024-Q3"), NaiveDate::from_ymd(2024, 9, 30));
+    assert_eq!(deadline("2024-Q4"), NaiveDate::from_ymd(2024, 12, 31));
+    assert_eq!(deadline("2024-W32"), NaiveDate::from_ymd(2024, 8, 11));
+
+    assert_eq!(Deadline::parse("2024-13", &[]), None);
+    assert_eq!(Deadline::parse("2024-Q5", &[]), None);
+    assert_eq!(Deadline::parse("2024-8-1", &[]), None);
+}
+
+#[test]
+fn custom_date_formats_are_read_from_the_config_file() {
+    let mut cmd = Command::cargo_bin("todo-ci").unwrap();
+    cmd.args(["--no-error"])
+        .current_dir("./tests/deadlines/fixtures")
+        .assert()
+        .stdout(predicates::str::contains("2221-Q3"))
+        .stdout(predicates::str::contains("2221-07-10T17:00"))
+        .stdout(predicates::str::contains("7 todo(s) found"))
+        .success();
+}
@@ -6,6 +6,7 @@ use todo_ci::{
     cli::DisplayMode,
     core::{SearchResult, Todo, TodoState, TodoStatistics},
     display,
+    severity::Severity,
 };
 
 fn sample_todo_search_result() -> SearchResult {
@@ -14,31 +15,65 @@ fn sample_todo_search_result() -> SearchResult {
             Todo {
                 file: Path::new("./file.rs").into(),
                 line_number: 3,
-                date: Some(NaiveDate::from_ymd(2221, 7, 10)),
+                end_line_number: 3,
+                deadline: Some(NaiveDate::from_ymd(2221, 7, 10).into()),
+                condition: None,
                 description: String::from("Valid TODO on line 3"),
+                tags: vec![],
+                owner: None,
                 state: TodoState::Valid,
+                suggestion: None,
+                severity: None,
+                rule: None,
+                suppression: None,
+                repository: None,
+                package: None,
             },
             Todo {
                 file: Path::new("./file.rs").into(),
                 line_number: 5,
-                date: Some(NaiveDate::from_ymd(1990, 7, 10)),
+                end_line_number: 5,
+                deadline: Some(NaiveDate::from_ymd(1990, 7, 10).into()),
+                condition: None,
                 description: String::from("Expired TODO on line 5"),
+                tags: vec![],
+                owner: None,
                 state: TodoState::Overdue,
+                suggestion: None,
+                severity: Some(Severity::Error),
+                rule: None,
+                suppression: None,
+                repository: None,
+                package: None,
             },
             Todo {
                 file: Path::new("./file.rs").into(),
                 line_number: 7,
-                date: None,
+                end_line_number: 7,
+                deadline: None,
+                condition: None,
                 description: String::from("XX is not a valid date."),
+                tags: vec![],
+                owner: None,
                 state: TodoState::Malformed,
+                suggestion: None,
+                severity: Some(Severity::Warning),
+                rule: None,
+                suppression: None,
+                repository: None,
+                package: None,
             },
         ],
+        suppressed_todos: vec![],
+        suppressions: vec![],
         statistics: TodoStatistics {
             files_searched: 1,
             valid_todo_count: 1,
             overdue_todo_count: 1,
             malformed_todo_count: 1,
+            suppressed_todo_count: 0,
         },
+        packages: vec![],
     }
 }
 
@@ -63,8 +98,12 @@ fn test_default_terminal_output() {
     assert!(output.contains("Expired TODO on line 5"));
     assert!(output.contains("XX is not a valid date."));
 
+    assert!(output.contains("Severity:    error"));
+    assert!(output.contains("Severity:    warning"));
+
     assert!(output.contains("Searched 1 file(s):"));
     assert!(output.contains("2 todo(s) found of which 1 is/are overdue"));
+    assert!(output.contains("1 error(s), 1 warning(s)"));
 }
 
 #[test]
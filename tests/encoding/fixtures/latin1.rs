@@ -0,0 +1,5 @@
+
+fn main() {
+    // @todo(2221-07-10): Caf latin-1 TODO on line 3
+    println!("Hello World!");
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use todo_ci::{core::SearchOptions, encoding::EncodingMode};
+
+#[test]
+fn find_todos_in_non_utf8_files() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/encoding/fixtures").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    // The binary file is skipped
+    assert_eq!(search_results.statistics.files_searched, 3);
+    assert_eq!(search_results.statistics.valid_todo_count, 3);
+
+    let descriptions: Vec<&str> = search_results
+        .todos
+        .iter()
+        .map(|todo| todo.description.as_str())
+        .collect();
+
+    assert!(descriptions.contains(&"Café latin-1 TODO on line 3"));
+    assert!(descriptions.contains(&"UTF-16 TODO on line 3"));
+    assert!(descriptions.contains(&"UTF-16BE TODO on line 3"));
+    assert!(search_results
+        .todos
+        .iter()
+        .all(|todo| todo.line_number == 3));
+}
+
+#[test]
+fn find_todos_in_non_utf8_files_lossily() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/encoding/fixtures").to_path_buf(),
+        &SearchOptions {
+            include: vec!["*.rs".to_string()],
+            encoding_mode: EncodingMode::Lossy,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.files_searched, 1);
+    assert_eq!(
+        search_results.todos[0].description,
+        "Caf\u{FFFD} latin-1 TODO on line 3"
+    );
+}
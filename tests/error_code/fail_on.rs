@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use chrono::{Duration, Utc};
+use todo_ci::exit_code;
+
+/// Creates a directory with a single file with `contents`
+fn fixture(contents: &str) -> tempfile::TempDir {
+    let root_directory = tempfile::tempdir().unwrap();
+    std::fs::write(root_directory.path().join("fixture.rs"), contents).unwrap();
+    root_directory
+}
+
+#[test]
+fn test_malformed_todos_do_not_fail_by_default() {
+    let root_directory = fixture("// @todo(XX): Malformed TODO on line 1\n");
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .current_dir(root_directory.path())
+        .assert()
+        .code(exit_code::SUCCESS);
+}
+
+#[test]
+fn test_exit_code_for_malformed_todos() {
+    let root_directory = fixture("// @todo(XX): Malformed TODO on line 1\n");
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--fail-on", "overdue,malformed"])
+        .current_dir(root_directory.path())
+        .assert()
+        .code(exit_code::MALFORMED);
+}
+
+#[test]
+fn test_exit_code_for_todos_due_soon() {
+    let due = (Utc::now() + Duration::days(3)).format("%Y-%m-%d");
+    let root_directory = fixture(&format!("// @todo({}): TODO due soon on line 1\n", due));
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--fail-on", "due-soon"])
+        .current_dir(root_directory.path())
+        .assert()
+        .code(exit_code::DUE_SOON);
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--fail-on", "due-soon", "--due-soon", "1"])
+        .current_dir(root_directory.path())
+        .assert()
+        .code(exit_code::SUCCESS);
+}
+
+#[test]
+fn test_overdue_todos_take_precedence() {
+    let root_directory = fixture(
+        "// @todo(XX): Malformed TODO on line 1\n// @todo(1990-01-01): Overdue TODO on line 2\n",
+    );
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--fail-on", "malformed,overdue"])
+        .current_dir(root_directory.path())
+        .assert()
+        .code(exit_code::OVERDUE);
+}
+
+#[test]
+fn test_exit_code_for_errors() {
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .arg("./does-not-exist")
+        .assert()
+        .code(exit_code::ERROR)
+        .stderr(predicates::str::contains("does-not-exist does not exist"));
+}
@@ -1,3 +1,5 @@
+mod fail_on;
+
 use assert_cmd::Command;
 
 #[test]
@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use todo_ci::{core::SearchOptions, explain::Explanation, source::SourceKind};
+
+use crate::ignores::fine_grained::{fixture, root_directory};
+
+fn explain(root_directory: &Path, path: &str, options: SearchOptions) -> Explanation {
+    todo_ci::explain::explain(root_directory, &root_directory.join(path), &options).unwrap()
+}
+
+#[test]
+fn explain_searched_files() {
+    let fixture = fixture();
+    let root_directory = root_directory(&fixture);
+
+    let explanation = explain(&root_directory, "main.rs", SearchOptions::default());
+    assert!(explanation.searched);
+    assert_eq!(explanation.reasons, vec!["it is not ignored"]);
+
+    let explanation = explain(
+        &root_directory,
+        "main.rs",
+        SearchOptions {
+            include: vec!["*.md".to_string(), "*.rs".to_string()],
+            ..Default::default()
+        },
+    );
+    assert!(explanation.searched);
+    assert_eq!(explanation.reasons, vec!["it matches --include `*.rs`"]);
+}
+
+#[test]
+fn explain_files_skipped_by_the_walker() {
+    let fixture = fixture();
+    let root_directory = root_directory(&fixture);
+
+    let explanation = explain(&root_directory, "build/output.rs", SearchOptions::default());
+    assert!(!explanation.searched);
+    assert_eq!(
+        explanation.reasons,
+        vec![format!(
+            "it is ignored by `build/` in {}/.gitignore",
+            root_directory.display()
+        )]
+    );
+
+    let explanation = explain(
+        &root_directory,
+        ".github/workflow.yml",
+        SearchOptions::default(),
+    );
+    assert!(!explanation.searched);
+    assert_eq!(
+        explanation.reasons,
+        vec!["it is hidden (use --hidden to search it)"]
+    );
+
+    let explanation = explain(
+        &root_directory,
+        "generated.rs",
+        SearchOptions {
+            ignore_file_names: vec![".todoignore".to_string()],
+            ..Default::default()
+        },
+    );
+    assert!(!explanation.searched);
+    assert_eq!(
+        explanation.reasons,
+        vec![format!(
+            "it is ignored by `generated.rs` in {}/.todoignore",
+            root_directory.display()
+        )]
+    );
+
+    let explanation = explain(
+        &root_directory,
+        "nested/deeper/deep.rs",
+        SearchOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        },
+    );
+    assert!(!explanation.searched);
+    assert_eq!(
+        explanation.reasons,
+        vec!["it is at depth 3, deeper than --max-depth 1"]
+    );
+}
+
+#[test]
+fn explain_files_skipped_by_globs() {
+    let fixture = fixture();
+    let root_directory = root_directory(&fixture);
+
+    let explanation = explain(
+        &root_directory,
+        "nested/deeper/deep.rs",
+        SearchOptions {
+            exclude: vec!["nested/".to_string()],
+            ..Default::default()
+        },
+    );
+    assert!(!explanation.searched);
+    assert_eq!(explanation.reasons, vec!["it matches --exclude `nested/`"]);
+
+    let explanation = explain(
+        &root_directory,
+        "main.rs",
+        SearchOptions {
+            include: vec!["*.md".to_string()],
+            ..Default::default()
+        },
+    );
+    assert!(!explanation.searched);
+    assert_eq!(
+        explanation.reasons,
+        vec!["it does not match any --include glob"]
+    );
+}
+
+#[test]
+fn test_files_are_listed() {
+    let fixture = fixture();
+    let root_directory = root_directory(&fixture);
+
+    let mut cmd = Command::cargo_bin("todo-ci").unwrap();
+    cmd.arg("files")
+        .arg(&root_directory)
+        .args(["--exclude", "nested/"])
+        .assert()
+        .stdout(predicates::str::contains("main.rs"))
+        .stdout(predicates::str::contains("generated.rs"))
+        .stdout(predicates::str::contains("deep.rs").not())
+        .stdout(predicates::str::contains("would be searched").not())
+        .stderr(predicates::str::contains("2 file(s) would be searched"))
+        .success();
+}
+
+#[test]
+fn test_other_sources_cant_be_explained() {
+    let fixture = fixture();
+    let root_directory = root_directory(&fixture);
+
+    let result = todo_ci::explain::explain(
+        &root_directory,
+        &root_directory.join("main.rs"),
+        &SearchOptions {
+            source: SourceKind::Index,
+            ..Default::default()
+        },
+    );
+    assert!(result.is_err());
+
+    let mut cmd = Command::cargo_bin("todo-ci").unwrap();
+    cmd.args(["--rev", "HEAD", "explain", "main.rs"])
+        .arg(&root_directory)
+        .assert()
+        .stderr(predicates::str::contains(
+            "explain can't be used with --rev",
+        ))
+        .code(todo_ci::exit_code::ERROR);
+}
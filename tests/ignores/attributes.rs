@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use todo_ci::core::{SearchOptions, SearchResult};
+
+const ROOT_DIRECTORY: &str = "./tests/ignores/fixtures/attributes";
+
+fn search(options: SearchOptions) -> SearchResult {
+    todo_ci::core::search(Path::new(ROOT_DIRECTORY).to_path_buf(), &options).unwrap()
+}
+
+fn searched_files(search_results: &SearchResult) -> Vec<String> {
+    let mut files: Vec<String> = search_results
+        .todos
+        .iter()
+        .map(|todo| {
+            todo.file
+                .strip_prefix(ROOT_DIRECTORY)
+                .unwrap()
+                .display()
+                .to_string()
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn find_todos_skipping_generated_and_vendored_files() {
+    let search_results = search(SearchOptions::default());
+
+    assert_eq!(
+        searched_files(&search_results),
+        vec!["main.rs", "vendor/patched.rs"]
+    );
+}
+
+#[test]
+fn find_todos_without_gitattributes() {
+    let search_results = search(SearchOptions {
+        no_gitattributes: true,
+        ..Default::default()
+    });
+
+    assert_eq!(
+        searched_files(&search_results),
+        vec![
+            "api.pb.rs",
+            "docs/syntax.md",
+            "main.rs",
+            "vendor/lib.rs",
+            "vendor/patched.rs"
+        ]
+    );
+}
+
+#[test]
+fn explain_files_skipped_by_gitattributes() {
+    let explanation = todo_ci::explain::explain(
+        Path::new(ROOT_DIRECTORY),
+        &Path::new(ROOT_DIRECTORY).join("vendor/lib.rs"),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert!(!explanation.searched);
+    assert_eq!(explanation.reasons.len(), 1);
+    assert!(explanation.reasons[0].starts_with("it is marked `linguist-vendored` in "));
+}
@@ -1,15 +1,13 @@
 use std::path::Path;
 
-use chrono::FixedOffset;
 use pretty_assertions::assert_eq;
+use todo_ci::core::SearchOptions;
 
 #[test]
 fn find_todos_with_default_ignore_files() {
     let search_results = todo_ci::core::search(
         Path::new("./tests/ignores/fixtures/default").to_path_buf(),
-        false,
-        "*".to_string(),
-        &FixedOffset::west(0),
+        &SearchOptions::default(),
     )
     .unwrap();
 
@@ -21,9 +19,10 @@ fn find_todos_with_default_ignore_files() {
 fn find_todos_without_default_ignore_files() {
     let search_results = todo_ci::core::search(
         Path::new("./tests/ignores/fixtures/default").to_path_buf(),
-        true,
-        "*".to_string(),
-        &FixedOffset::west(0),
+        &SearchOptions {
+            no_ignore: true,
+            ..Default::default()
+        },
     )
     .unwrap();
 
@@ -0,0 +1,172 @@
+use std::{fs, path::PathBuf};
+
+use pretty_assertions::assert_eq;
+use todo_ci::core::{SearchOptions, SearchResult};
+
+fn write(path: PathBuf, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+/// Creates the fixture in `fine_grained/` of a temporary directory, with a `.tdignore` above it
+///
+/// `.gitignore` files only apply inside of a repository, so the fixture is created at runtime to be one
+/// even when this crate isn't tested in a checkout. Its `.git` directory is left empty, so that no files
+/// of it are searched along with hidden files.
+pub fn fixture() -> tempfile::TempDir {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path();
+
+    write(path.join(".tdignore"), "README.md\n");
+    let root_directory = root_directory(&directory);
+    fs::create_dir_all(root_directory.join(".git")).unwrap();
+    for (file, contents) in [
+        (".gitignore", "build/\n"),
+        (".todoignore", "generated.rs\n"),
+        (
+            ".github/workflow.yml",
+            "# @todo(2221-07-10): Hidden TODO on line 1\n",
+        ),
+        (
+            "README.md",
+            "<!-- @todo(2221-07-10): TODO in README.md -->\n",
+        ),
+        (
+            "build/output.rs",
+            "// @todo(2221-07-10): TODO in build/output.rs\n",
+        ),
+        (
+            "generated.rs",
+            "// @todo(2221-07-10): TODO in generated.rs\n",
+        ),
+        ("main.rs", "// @todo(2221-07-10): TODO in main.rs\n"),
+        (
+            "nested/deeper/deep.rs",
+            "// @todo(2221-07-10): TODO in nested/deeper/deep.rs\n",
+        ),
+    ] {
+        write(root_directory.join(file), contents);
+    }
+
+    directory
+}
+
+/// The root directory to search of a fixture, see `fixture`
+pub fn root_directory(fixture: &tempfile::TempDir) -> PathBuf {
+    fixture.path().join("fine_grained")
+}
+
+fn search(options: SearchOptions) -> SearchResult {
+    let fixture = fixture();
+    let root_directory = root_directory(&fixture);
+    let mut search_results = todo_ci::core::search(root_directory.clone(), &options).unwrap();
+
+    for todo in &mut search_results.todos {
+        todo.file = todo.file.strip_prefix(&root_directory).unwrap().into();
+    }
+    search_results
+}
+
+fn searched_files(search_results: &SearchResult) -> Vec<String> {
+    let mut files: Vec<String> = search_results
+        .todos
+        .iter()
+        .map(|todo| todo.file.display().to_string())
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn find_todos_with_default_ignores() {
+    let search_results = search(SearchOptions::default());
+
+    assert_eq!(
+        searched_files(&search_results),
+        vec!["generated.rs", "main.rs", "nested/deeper/deep.rs"]
+    );
+}
+
+#[test]
+fn find_todos_in_hidden_files_while_respecting_gitignore() {
+    let search_results = search(SearchOptions {
+        hidden: true,
+        ..Default::default()
+    });
+
+    assert_eq!(
+        searched_files(&search_results),
+        vec![
+            ".github/workflow.yml",
+            "generated.rs",
+            "main.rs",
+            "nested/deeper/deep.rs"
+        ]
+    );
+}
+
+#[test]
+fn find_todos_without_gitignore() {
+    let search_results = search(SearchOptions {
+        no_gitignore: true,
+        ..Default::default()
+    });
+
+    assert_eq!(
+        searched_files(&search_results),
+        vec![
+            "build/output.rs",
+            "generated.rs",
+            "main.rs",
+            "nested/deeper/deep.rs"
+        ]
+    );
+}
+
+#[test]
+fn find_todos_without_parent_ignore_files() {
+    let search_results = search(SearchOptions {
+        no_parent_ignore: true,
+        ..Default::default()
+    });
+
+    // README.md is only ignored by the .tdignore above the fixture
+    assert_eq!(
+        searched_files(&search_results),
+        vec![
+            "README.md",
+            "generated.rs",
+            "main.rs",
+            "nested/deeper/deep.rs"
+        ]
+    );
+}
+
+#[test]
+fn find_todos_up_to_a_max_depth() {
+    let search_results = search(SearchOptions {
+        max_depth: Some(1),
+        ..Default::default()
+    });
+
+    assert_eq!(
+        searched_files(&search_results),
+        vec!["generated.rs", "main.rs"]
+    );
+}
+
+#[test]
+fn find_todos_with_custom_ignore_files() {
+    let search_results = search(SearchOptions {
+        hidden: true,
+        ignore_file_names: vec![".todoignore".to_string()],
+        ..Default::default()
+    });
+
+    assert_eq!(
+        searched_files(&search_results),
+        vec![".github/workflow.yml", "main.rs", "nested/deeper/deep.rs"]
+    );
+    // The ignore files themselves are not searched, other than .gitignore
+    assert_eq!(search_results.statistics.files_searched, 4);
+}
@@ -0,0 +1 @@
+// @todo(2222-01-01): A todo in main.rs
@@ -0,0 +1 @@
+// @todo(2221-07-10): Generated TODO on line 1
@@ -1,3 +1,5 @@
+mod attributes;
 mod default_ignores;
+pub mod fine_grained;
 mod patterns;
 mod tdignore;
@@ -1,7 +1,7 @@
 use std::path::Path;
 
-use chrono::FixedOffset;
 use pretty_assertions::assert_eq;
+use todo_ci::core::SearchOptions;
 
 #[test]
 /// Test is not too comprehensive as by doing so, we would just be testing ripgrep.
@@ -9,12 +9,75 @@ use pretty_assertions::assert_eq;
 fn find_todos_using_a_filename_pattern() {
     let search_results = todo_ci::core::search(
         Path::new("./tests/ignores/fixtures/patterns").to_path_buf(),
-        false,
-        "*be_scanned*".to_string(),
-        &FixedOffset::west(0),
+        &SearchOptions {
+            include: vec!["*be_scanned*".to_string()],
+            ..Default::default()
+        },
     )
     .unwrap();
 
     assert_eq!(search_results.statistics.files_searched, 2);
     assert_eq!(search_results.statistics.valid_todo_count, 2);
 }
+
+#[test]
+fn find_todos_using_include_and_exclude_globs() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/ignores/fixtures/patterns").to_path_buf(),
+        &SearchOptions {
+            include: vec!["*.rs".to_string(), "*.ts".to_string()],
+            exclude: vec!["generated/".to_string(), "will_be_ignored.rs".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.files_searched, 3);
+
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/ignores/fixtures/patterns").to_path_buf(),
+        &SearchOptions {
+            exclude: vec!["*.pb.rs".to_string(), "*ignored*".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.files_searched, 2);
+}
+
+#[test]
+fn find_todos_using_file_types() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/ignores/fixtures/patterns").to_path_buf(),
+        &SearchOptions {
+            file_types: vec!["rust".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.files_searched, 4);
+
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/ignores/fixtures/patterns").to_path_buf(),
+        &SearchOptions {
+            file_types: vec!["rust".to_string(), "ts".to_string()],
+            exclude: vec!["generated/".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.files_searched, 4);
+
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/ignores/fixtures/patterns").to_path_buf(),
+        &SearchOptions {
+            file_types: vec!["not-a-type".to_string()],
+            ..Default::default()
+        },
+    );
+
+    assert!(search_results.is_err());
+}
@@ -1,15 +1,13 @@
 use std::path::Path;
 
-use chrono::FixedOffset;
 use pretty_assertions::assert_eq;
+use todo_ci::core::SearchOptions;
 
 #[test]
 fn test_that_tdignore_cannot_be_disabled() {
     let search_results = todo_ci::core::search(
         Path::new("./tests/ignores/fixtures/tdignore").to_path_buf(),
-        false,
-        "*".to_string(),
-        &FixedOffset::west(0),
+        &SearchOptions::default(),
     )
     .unwrap();
 
@@ -18,9 +16,10 @@ fn test_that_tdignore_cannot_be_disabled() {
 
     let search_results = todo_ci::core::search(
         Path::new("./tests/ignores/fixtures/tdignore").to_path_buf(),
-        true,
-        "*".to_string(),
-        &FixedOffset::west(0),
+        &SearchOptions {
+            no_ignore: true,
+            ..Default::default()
+        },
     )
     .unwrap();
 
@@ -1,4 +1,20 @@
+mod archives;
+mod comments;
+mod conditions;
+mod deadlines;
 mod display;
+mod encoding;
 mod error_code;
+mod explain;
 mod ignores;
+mod multiline;
+mod near_miss;
+mod policy;
+mod relative;
+mod repositories;
+mod rules;
+mod severity;
 mod simple;
+mod sources;
+mod suppressions;
+mod workspaces;
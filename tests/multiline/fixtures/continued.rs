@@ -0,0 +1,18 @@
+
+fn main() {
+    // @todo(2221-07-10): Line comment TODO on line 3
+    // that continues on line 4
+    // and ends on line 5
+    //
+    // Not part of the TODO on line 7
+    println!("Hello World!"); // @todo(2221-07-10): Trailing TODO on line 8
+                              // that continues on line 9
+    /*
+     * @todo(2221-07-10): Block comment TODO on line 11
+     * that continues on line 12
+     */
+    /* @todo(2221-07-10): Block comment TODO on line 14
+       that ends on line 15 */
+    // @todo(2221-07-10): Single line TODO on line 16
+    println!("Hello World again!");
+}
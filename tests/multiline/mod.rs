@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use todo_ci::core::SearchOptions;
+
+#[test]
+fn find_todos_with_descriptions_over_several_lines() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/multiline/fixtures").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.valid_todo_count, 5);
+
+    let found: Vec<(i32, i32, &str)> = search_results
+        .todos
+        .iter()
+        .map(|todo| {
+            (
+                todo.line_number,
+                todo.end_line_number,
+                todo.description.as_str(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        found,
+        vec![
+            (
+                3,
+                5,
+                "Line comment TODO on line 3 that continues on line 4 and ends on line 5"
+            ),
+            (8, 9, "Trailing TODO on line 8 that continues on line 9"),
+            (
+                11,
+                12,
+                "Block comment TODO on line 11 that continues on line 12"
+            ),
+            (14, 15, "Block comment TODO on line 14 that ends on line 15"),
+            (16, 16, "Single line TODO on line 16"),
+        ]
+    );
+}
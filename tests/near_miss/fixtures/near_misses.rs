@@ -0,0 +1,12 @@
+
+fn main() {
+    // @TODO(2221-07-10): Uppercase TODO on line 3
+    // @todo (2221-07-10): Space before the date on line 4
+    // @todo(2221-7-10): Unpadded date on line 5
+    // @todo[2221-07-10]: Square brackets on line 6
+    // @todo(2221-07-10) Missing colon on line 7
+    // @todo(2221-07-10): Valid TODO on line 8
+    println!("@TODO(2221-07-10): Not a TODO as it is inside of a string");
+    // @todoo(2021-07-10): This todo shouldn't be registered
+    // @todo(<date>):<description> as a placeholder on line 11
+}
@@ -0,0 +1,62 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use todo_ci::core::{SearchOptions, TodoState};
+
+#[test]
+fn find_near_miss_todos() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/near_miss/fixtures").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(search_results.statistics.valid_todo_count, 1);
+    assert_eq!(search_results.statistics.malformed_todo_count, 6);
+
+    let malformed: Vec<(i32, &str, Option<&str>)> = search_results
+        .todos
+        .iter()
+        .filter(|todo| matches!(todo.state, TodoState::Malformed))
+        .map(|todo| {
+            (
+                todo.line_number,
+                todo.description.as_str(),
+                todo.suggestion.as_deref(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        malformed,
+        vec![
+            (
+                3,
+                "`@TODO` should be written as `@todo`.",
+                Some("@todo(2221-07-10): Uppercase TODO on line 3")
+            ),
+            (
+                4,
+                "There should be no space before the date.",
+                Some("@todo(2221-07-10): Space before the date on line 4")
+            ),
+            (
+                5,
+                "2221-7-10 should be formatted as YYYY-MM-DD.",
+                Some("@todo(2221-07-10): Unpadded date on line 5")
+            ),
+            (
+                6,
+                "The date should be wrapped in `(...)` instead of `[...]`.",
+                Some("@todo(2221-07-10): Square brackets on line 6")
+            ),
+            (
+                7,
+                "The date should be followed by a colon.",
+                Some("@todo(2221-07-10): Missing colon on line 7")
+            ),
+            // Placeholders are reported without a suggestion
+            (11, "<date> is not a valid date.", None),
+        ]
+    );
+}
@@ -0,0 +1,7 @@
+
+fn main() {
+    // @todo(2221-01-01): Valid TODO on line 3
+    // @todo(2221-01-01): Valid TODO on line 4
+    // @todo(1990-01-01): Overdue TODO on line 5
+    // @todo(XX): Malformed TODO on line 6
+}
@@ -0,0 +1,6 @@
+
+#[test]
+fn test() {
+    // @todo(2221-01-01): Valid TODO on line 4
+    // @todo(2221-01-01): Valid TODO on line 5
+}
@@ -0,0 +1,262 @@
+use std::path::Path;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use todo_ci::{
+    core::{SearchOptions, SearchResult},
+    exit_code,
+    policy::{Budget, PathBudget, Ratchet},
+};
+
+fn search(root_directory: &Path) -> SearchResult {
+    todo_ci::core::search(root_directory.to_path_buf(), &SearchOptions::default()).unwrap()
+}
+
+#[test]
+fn check_todo_budgets() {
+    let root_directory = Path::new("./tests/policy/fixtures");
+    let budget = Budget {
+        max_todos: Some(4),
+        max_todos_per_file: Some(2),
+        paths: vec![
+            PathBudget {
+                path: "tests/".to_string(),
+                max_todos: Some(0),
+                max_new_todos: None,
+            },
+            PathBudget {
+                path: "src/*.rs".to_string(),
+                max_todos: Some(3),
+                max_new_todos: None,
+            },
+        ],
+    };
+
+    let violations = todo_ci::policy::check(
+        &budget,
+        &Ratchet::default(),
+        &search(root_directory),
+        root_directory,
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        violations,
+        vec![
+            "5 todo(s) were found, but the budget is 4".to_string(),
+            "3 todo(s) were found in ./tests/policy/fixtures/src/main.rs, but the budget is 2 per file"
+                .to_string(),
+            "2 todo(s) were found in tests/, but the budget is 0".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn check_todo_ratchet() {
+    let root_directory = tempfile::tempdir().unwrap();
+    let source_file = root_directory.path().join("main.rs");
+    let ratchet = Ratchet {
+        file: Some("ratchet.json".into()),
+    };
+    let check = || {
+        todo_ci::policy::check(
+            &Budget::default(),
+            &ratchet,
+            &search(root_directory.path()),
+            root_directory.path(),
+            &SearchOptions::default(),
+        )
+        .unwrap()
+    };
+    let check_filtered = || {
+        let options = SearchOptions {
+            include: vec!["other.rs".to_string()],
+            ..Default::default()
+        };
+        todo_ci::policy::check(
+            &Budget::default(),
+            &ratchet,
+            &todo_ci::core::search(root_directory.path().to_path_buf(), &options).unwrap(),
+            root_directory.path(),
+            &options,
+        )
+        .unwrap()
+    };
+
+    // The first run stores the current numbers
+    std::fs::write(
+        &source_file,
+        "// @todo(2221-01-01): Valid TODO\n// @todo(2221-01-01): Valid TODO\n",
+    )
+    .unwrap();
+    assert!(check().is_empty());
+
+    // Filtered runs neither lower nor enforce the ratchet
+    assert!(check_filtered().is_empty());
+    assert!(check().is_empty());
+
+    // Fewer todos lower the ratchet
+    std::fs::write(&source_file, "// @todo(2221-01-01): Valid TODO\n").unwrap();
+    assert!(check().is_empty());
+
+    std::fs::write(
+        &source_file,
+        "// @todo(2221-01-01): Valid TODO\n// @todo(1990-01-01): Overdue TODO\n",
+    )
+    .unwrap();
+    assert_eq!(
+        check(),
+        vec![
+            "the number of todos increased from 1 to 2".to_string(),
+            "the number of overdue todos increased from 0 to 1".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn check_new_todos_in_paths() {
+    let root_directory = tempfile::tempdir().unwrap();
+    let test_file = root_directory.path().join("tests/test.rs");
+    std::fs::create_dir_all(test_file.parent().unwrap()).unwrap();
+    let budget = Budget {
+        paths: vec![PathBudget {
+            path: "tests/".to_string(),
+            max_todos: None,
+            max_new_todos: Some(0),
+        }],
+        ..Default::default()
+    };
+    let check = |ratchet: &Ratchet| {
+        todo_ci::policy::check(
+            &budget,
+            ratchet,
+            &search(root_directory.path()),
+            root_directory.path(),
+            &SearchOptions::default(),
+        )
+    };
+    let ratchet = Ratchet {
+        file: Some("ratchet.json".into()),
+    };
+
+    // Existing todos are not new
+    std::fs::write(&test_file, "// @todo(2221-01-01): Existing TODO\n").unwrap();
+    assert!(check(&ratchet).unwrap().is_empty());
+    assert!(check(&ratchet).unwrap().is_empty());
+
+    std::fs::write(
+        &test_file,
+        "// @todo(2221-01-01): Existing TODO\n// @todo(2221-01-01): New TODO\n",
+    )
+    .unwrap();
+    assert_eq!(
+        check(&ratchet).unwrap(),
+        vec![
+            "the number of todos increased from 1 to 2".to_string(),
+            "1 new todo(s) were found in tests/, but the budget is 0 new todo(s)".to_string(),
+        ]
+    );
+
+    // New todos are counted against the ratchet
+    assert!(check(&Ratchet::default()).is_err());
+}
+
+#[test]
+fn test_exit_code_for_policy_violations() {
+    let root_directory = tempfile::tempdir().unwrap();
+    std::fs::write(
+        root_directory.path().join("main.rs"),
+        "// @todo(2221-01-01): Valid TODO\n// @todo(2221-01-01): Valid TODO\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root_directory.path().join("todo-ci.toml"),
+        "[budget]\nmax-todos = 1\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--config", "todo-ci.toml"])
+        .current_dir(root_directory.path())
+        .assert()
+        .code(exit_code::POLICY_VIOLATION)
+        .stdout(predicates::str::contains(
+            "2 todo(s) were found, but the budget is 1",
+        ));
+}
+
+#[test]
+fn test_policies_skip_listed_files_and_stdin() {
+    let root_directory = tempfile::tempdir().unwrap();
+    std::fs::write(
+        root_directory.path().join("main.rs"),
+        "// @todo(2221-01-01): Valid TODO\n// @todo(2221-01-01): Valid TODO\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root_directory.path().join(".todo-ci.toml"),
+        "[budget]\nmax-todos = 1\n\n[ratchet]\nfile = \"ratchet.json\"\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--files-from", "-"])
+        .write_stdin("main.rs\n")
+        .current_dir(root_directory.path())
+        .assert()
+        .success();
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["-", "--stdin-filename", "main.rs"])
+        .write_stdin("")
+        .current_dir(root_directory.path())
+        .assert()
+        .success();
+    assert!(!root_directory.path().join("ratchet.json").exists());
+}
+
+#[test]
+fn test_policies_skip_several_root_directories() {
+    let root_directory = tempfile::tempdir().unwrap();
+    let path = root_directory.path();
+    for (file, contents) in [
+        ("a/main.rs", "// @todo(2221-01-01): Valid TODO\n"),
+        (
+            "b/main.rs",
+            "// @todo(2221-01-01): Valid TODO\n// @todo(2221-01-01): Valid TODO\n",
+        ),
+        (
+            "a/.todo-ci.toml",
+            "[budget]\npaths = [{ path = \"*.rs\", max-todos = 0 }]\n\n[ratchet]\nfile = \"ratchet.json\"\n",
+        ),
+    ] {
+        std::fs::create_dir_all(path.join(file).parent().unwrap()).unwrap();
+        std::fs::write(path.join(file), contents).unwrap();
+    }
+    let run = |root_directories: &[&str]| {
+        Command::cargo_bin("todo-ci")
+            .unwrap()
+            .args(root_directories)
+            .current_dir(path)
+            .assert()
+    };
+
+    // Only the first root directory's own runs use its path budgets & ratchet
+    for _ in 0..2 {
+        run(&["a", "b"]).success();
+        run(&["a"])
+            .code(exit_code::POLICY_VIOLATION)
+            .stdout(predicates::str::contains(
+                "1 todo(s) were found in *.rs, but the budget is 0",
+            ))
+            .stdout(predicates::str::contains("increased").not());
+    }
+    assert_eq!(
+        std::fs::read_to_string(path.join("a/ratchet.json")).unwrap(),
+        "{\n  \"todos\": 1,\n  \"overdue\": 0\n}"
+    );
+}
@@ -0,0 +1,81 @@
+use std::{fs, path::Path, process::Command};
+
+use chrono::{Duration, NaiveDate, Utc};
+use pretty_assertions::assert_eq;
+use todo_ci::{
+    core::{SearchOptions, TodoState},
+    deadline::Deadline,
+};
+
+fn git(directory: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args([
+            "-c",
+            "user.name=todo-ci",
+            "-c",
+            "user.email=todo-ci@example.com",
+        ])
+        .args(args)
+        .current_dir(directory)
+        .env("GIT_AUTHOR_DATE", "2000-01-01T12:00:00Z")
+        .env("GIT_COMMITTER_DATE", "2000-01-01T12:00:00Z")
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn find_todos_with_deadlines_relative_to_their_commit() {
+    let repository = tempfile::tempdir().unwrap();
+    let file = repository.path().join("relative.rs");
+
+    fs::write(
+        &file,
+        "fn main() {\n    // @todo(+90d): Committed TODO on line 2\n    // @todo(P1M): Committed TODO on line 3\n}\n",
+    )
+    .unwrap();
+    git(repository.path(), &["init", "--quiet"]);
+    git(repository.path(), &["add", "."]);
+    git(repository.path(), &["commit", "--quiet", "-m", "Add TODOs"]);
+
+    fs::write(
+        &file,
+        "fn main() {\n    // @todo(+90d): Committed TODO on line 2\n    // @todo(P1M): Committed TODO on line 3\n    // @todo(+2w): Uncommitted TODO on line 4\n}\n",
+    )
+    .unwrap();
+
+    let search_results =
+        todo_ci::core::search(repository.path().to_path_buf(), &SearchOptions::default()).unwrap();
+
+    assert_eq!(search_results.statistics.overdue_todo_count, 2);
+    assert_eq!(search_results.statistics.valid_todo_count, 1);
+
+    let committed = NaiveDate::from_ymd(2000, 1, 1);
+    let today = Utc::now().naive_utc().date();
+    let due_dates: Vec<(NaiveDate, NaiveDate)> = search_results
+        .todos
+        .iter()
+        .map(|todo| match todo.deadline.as_ref().unwrap() {
+            Deadline::Relative { anchor, due, .. } => (*anchor, *due),
+            deadline => panic!("{:?} is not a relative deadline", deadline),
+        })
+        .collect();
+
+    assert_eq!(
+        due_dates,
+        vec![
+            (committed, NaiveDate::from_ymd(2000, 3, 31)),
+            (committed, NaiveDate::from_ymd(2000, 2, 1)),
+            (today, today + Duration::days(14)),
+        ]
+    );
+    assert!(matches!(search_results.todos[2].state, TodoState::Valid));
+    assert_eq!(
+        search_results.todos[0]
+            .deadline
+            .as_ref()
+            .unwrap()
+            .to_string(),
+        "2000-03-31 (+90d after 2000-01-01)"
+    );
+}
@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+use assert_cmd::Command;
+use pretty_assertions::assert_eq;
+use todo_ci::{
+    cli::NestedRepos,
+    core::{SearchOptions, SearchResult},
+    severity::Severity,
+};
+
+/// Creates a directory with a submodule in `libs/inner`, which configures its own severities
+///
+/// Nested repositories can't be committed as fixtures, as git doesn't allow adding `.git` files
+fn fixture() -> tempfile::TempDir {
+    let root_directory = tempfile::tempdir().unwrap();
+    let inner = root_directory.path().join("libs/inner");
+    std::fs::create_dir_all(&inner).unwrap();
+
+    std::fs::write(
+        root_directory.path().join("main.rs"),
+        "// @todo(2000-01-01): Overdue TODO of the root directory\n",
+    )
+    .unwrap();
+    std::fs::write(inner.join(".git"), "gitdir: ../../.git/modules/inner\n").unwrap();
+    std::fs::write(
+        inner.join(".todo-ci.toml"),
+        "[severity]\noverdue = \"warning\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        inner.join("lib.rs"),
+        "// @todo(2000-01-01): Overdue TODO of the submodule\n",
+    )
+    .unwrap();
+
+    root_directory
+}
+
+fn search(root_directory: &Path, nested_repos: NestedRepos) -> SearchResult {
+    todo_ci::core::search(
+        root_directory.to_path_buf(),
+        &SearchOptions {
+            nested_repos,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn repositories(search_results: &SearchResult) -> Vec<Option<PathBuf>> {
+    search_results
+        .todos
+        .iter()
+        .map(|todo| todo.repository.clone())
+        .collect()
+}
+
+#[test]
+fn find_todos_including_nested_repositories() {
+    let root_directory = fixture();
+    let search_results = search(root_directory.path(), NestedRepos::Include);
+
+    assert_eq!(
+        repositories(&search_results),
+        vec![None, Some(root_directory.path().join("libs/inner"))]
+    );
+    // The submodule's configuration is not used
+    assert_eq!(search_results.todos[1].severity, Some(Severity::Error));
+}
+
+#[test]
+fn find_todos_skipping_nested_repositories() {
+    let root_directory = fixture();
+    let search_results = search(root_directory.path(), NestedRepos::Skip);
+
+    assert_eq!(repositories(&search_results), vec![None]);
+}
+
+#[test]
+fn find_todos_in_nested_repositories_separately() {
+    let root_directory = fixture();
+    let search_results = search(root_directory.path(), NestedRepos::Separate);
+
+    assert_eq!(
+        repositories(&search_results),
+        vec![None, Some(root_directory.path().join("libs/inner"))]
+    );
+    assert_eq!(search_results.todos[0].severity, Some(Severity::Error));
+    assert_eq!(search_results.todos[1].severity, Some(Severity::Warning));
+    assert_eq!(search_results.statistics.overdue_todo_count, 2);
+}
+
+#[test]
+fn test_todos_grouped_by_repository_in_output() {
+    let root_directory = fixture();
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--nested-repos", "separate", "./"])
+        .current_dir(root_directory.path())
+        .assert()
+        .stdout(predicates::str::contains("Repository ./libs/inner:"))
+        .stdout(predicates::str::contains("root directory"))
+        .stdout(predicates::str::contains("1 todo(s), 1 overdue"));
+}
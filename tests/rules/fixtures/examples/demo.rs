@@ -0,0 +1,4 @@
+
+fn main() {
+    // @todo(1990-01-01): Overdue TODO on line 3
+}
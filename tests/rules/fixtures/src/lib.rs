@@ -0,0 +1,4 @@
+
+pub fn lib() {
+    // @todo(2221-01-01): Valid TODO on line 3
+}
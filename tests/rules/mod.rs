@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+
+use assert_cmd::Command;
+use pretty_assertions::assert_eq;
+use todo_ci::{config::Config, core::SearchOptions, severity::Severity};
+
+#[test]
+fn find_todos_with_path_rules() {
+    let root_directory = Path::new("./tests/rules/fixtures");
+    let config = Config::load(root_directory, None).unwrap();
+    let search_results = todo_ci::core::search(
+        root_directory.to_path_buf(),
+        &SearchOptions {
+            rules: config.rules,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut todos = search_results.todos;
+    todos.sort_by_key(|todo| todo.file.clone());
+
+    let files: Vec<PathBuf> = todos
+        .iter()
+        .map(|todo| todo.file.strip_prefix(root_directory).unwrap().into())
+        .collect();
+    assert_eq!(
+        files,
+        vec![
+            PathBuf::from("examples/demo.rs"),
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("vendor/dependency.rs"),
+        ]
+    );
+
+    assert_eq!(todos[0].severity, Some(Severity::Warning));
+    assert_eq!(todos[0].rule.as_ref().unwrap().paths, vec!["examples/"]);
+
+    assert_eq!(todos[1].severity, None);
+    assert!(todos[1].rule.is_none());
+
+    let vendor_rule = todos[2].rule.as_ref().unwrap();
+    assert_eq!(todos[2].severity, Some(Severity::Error));
+    assert_eq!(vendor_rule.paths, vec!["vendor/", "vendor/*.rs"]);
+    assert_eq!(vendor_rule.fail, Some(false));
+    assert_eq!(vendor_rule.due_soon, Some(30));
+}
+
+#[test]
+fn test_path_rules_in_verbose_output() {
+    let mut cmd = Command::cargo_bin("todo-ci").unwrap();
+    cmd.args(["--display-mode", "verbose"])
+        .current_dir("./tests/rules/fixtures")
+        .assert()
+        .stdout(predicates::str::contains(
+            "examples/ (overdue: warning, malformed: error)",
+        ))
+        .stdout(predicates::str::contains(
+            "vendor/, vendor/*.rs (due soon: 30 days, fail: false)",
+        ))
+        .stdout(predicates::str::contains("default"))
+        .success();
+}
+
+#[test]
+fn test_path_rules_require_tags_and_owners() {
+    let root_directory = tempfile::tempdir().unwrap();
+    std::fs::create_dir(root_directory.path().join("teams")).unwrap();
+    std::fs::write(
+        root_directory.path().join(".todo-ci.toml"),
+        "[[rules]]\npath = \"teams/\"\nallowed-tags = [\"perf\"]\nrequire-owner = true\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root_directory.path().join("teams/api.rs"),
+        "// @todo(2222-01-01): Cache responses #perf @platform-team\n// @todo(2222-01-01): Rename handlers #style, see #12\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--display-mode", "json"])
+        .current_dir(root_directory.path())
+        .assert()
+        .code(todo_ci::exit_code::POLICY_VIOLATION)
+        .get_output()
+        .stdout
+        .clone();
+    let document: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    let todos = document["todos"].as_array().unwrap();
+    assert_eq!(todos[0]["tags"], serde_json::json!(["perf"]));
+    assert_eq!(todos[0]["owner"], "platform-team");
+    assert_eq!(todos[1]["tags"], serde_json::json!(["style"]));
+    assert_eq!(todos[1]["rule"]["paths"], serde_json::json!(["teams/"]));
+    assert_eq!(
+        todos[1]["rule"]["allowed_tags"],
+        serde_json::json!(["perf"])
+    );
+    assert_eq!(todos[1]["rule"]["require_owner"], true);
+    assert_eq!(
+        document["policy_violations"],
+        serde_json::json!([
+            "./teams/api.rs:2: the tag #style is not allowed in teams/ (allowed: perf)",
+            "./teams/api.rs:2: an owner (i.e. `@team`) is required in teams/",
+        ])
+    );
+}
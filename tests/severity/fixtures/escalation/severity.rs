@@ -0,0 +1,9 @@
+
+fn main() {
+    // @todo(1990-01-01): Error TODO on line 3
+    // @todo(2010-01-01): Warning TODO on line 4
+    // @todo(1990-01-01, info): Info TODO on line 5
+    // @todo(2221-01-01, error): Valid TODO on line 6
+    // @todo(XX, error): Malformed TODO on line 7
+    // @todo(YY): Malformed TODO on line 8
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use assert_cmd::Command;
+use pretty_assertions::assert_eq;
+use todo_ci::{
+    core::SearchOptions,
+    severity::{Escalation, Severity, SeverityPolicy},
+};
+
+#[test]
+fn find_todos_with_severities() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/severity/fixtures/escalation").to_path_buf(),
+        &SearchOptions {
+            severity_policy: SeverityPolicy {
+                escalation: vec![
+                    Escalation {
+                        after_days: 0,
+                        severity: Severity::Warning,
+                    },
+                    Escalation {
+                        after_days: 10000,
+                        severity: Severity::Error,
+                    },
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut todos = search_results.todos;
+    todos.sort_by_key(|todo| todo.line_number);
+
+    let severities: Vec<(i32, Option<Severity>)> = todos
+        .iter()
+        .map(|todo| (todo.line_number, todo.severity))
+        .collect();
+    assert_eq!(
+        severities,
+        vec![
+            (3, Some(Severity::Error)),
+            (4, Some(Severity::Warning)),
+            (5, Some(Severity::Info)),
+            (6, None),
+            (7, Some(Severity::Error)),
+            (8, Some(Severity::Error)),
+        ]
+    );
+}
+
+#[test]
+fn test_error_code_not_emitted_for_warnings() {
+    let mut cmd = Command::cargo_bin("todo-ci").unwrap();
+    cmd.current_dir("./tests/severity/fixtures/warnings")
+        .assert()
+        .stdout(predicates::str::contains("warnings.rs:3"))
+        .stdout(predicates::str::contains("1 warning(s)"))
+        .success();
+}
@@ -1,7 +1,7 @@
 use std::path::Path;
 
-use chrono::{FixedOffset, NaiveDate};
-use todo_ci::core::TodoState;
+use chrono::NaiveDate;
+use todo_ci::core::{SearchOptions, TodoState};
 
 use pretty_assertions::assert_eq;
 
@@ -9,9 +9,7 @@ use pretty_assertions::assert_eq;
 fn find_valid_todos() {
     let search_results = todo_ci::core::search(
         Path::new("./tests/simple/fixtures").to_path_buf(),
-        false,
-        "*".to_string(),
-        &FixedOffset::west(0),
+        &SearchOptions::default(),
     )
     .unwrap();
 
@@ -29,7 +27,10 @@ fn find_valid_todos() {
         valid_todo.file.as_path(),
         Path::new("./tests/simple/fixtures/file_with_todos.rs")
     );
-    assert_eq!(valid_todo.date.unwrap(), NaiveDate::from_ymd(2221, 7, 10));
+    assert_eq!(
+        valid_todo.deadline,
+        Some(NaiveDate::from_ymd(2221, 7, 10).into())
+    );
     assert_eq!(valid_todo.description, "I am a valid TODO on line 4");
     assert_eq!(valid_todo.line_number, 4);
 }
@@ -0,0 +1,333 @@
+use std::{fs, path::Path, process::Command};
+
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use todo_ci::{
+    core::{SearchOptions, SearchResult, TodoState},
+    source::SourceKind,
+};
+
+fn git(directory: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args([
+            "-c",
+            "user.name=todo-ci",
+            "-c",
+            "user.email=todo-ci@example.com",
+        ])
+        .args(args)
+        .current_dir(directory)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+/// Creates a repository with a commit tagged `v1`, staged changes on top of it and an untracked file
+fn fixture() -> tempfile::TempDir {
+    let repository = tempfile::tempdir().unwrap();
+    let path = repository.path();
+
+    fs::write(path.join(".tdignore"), "ignored.rs\n").unwrap();
+    fs::write(
+        path.join("ignored.rs"),
+        "// @todo(2222-01-01): Ignored TODO\n",
+    )
+    .unwrap();
+    fs::write(path.join("a.rs"), "// @todo(2222-01-01): Committed TODO\n").unwrap();
+    git(path, &["init", "--quiet"]);
+    git(path, &["add", "."]);
+    git(path, &["commit", "--quiet", "-m", "Add TODOs"]);
+    git(path, &["tag", "v1"]);
+
+    fs::write(path.join("a.rs"), "fn main() {}\n").unwrap();
+    fs::write(path.join("b.rs"), "// @todo(2222-01-01): Staged TODO\n").unwrap();
+    git(path, &["add", "."]);
+    fs::write(path.join("c.rs"), "// @todo(2222-01-01): Untracked TODO\n").unwrap();
+
+    repository
+}
+
+fn search(root_directory: &Path, source: SourceKind) -> SearchResult {
+    todo_ci::core::search(
+        root_directory.to_path_buf(),
+        &SearchOptions {
+            source,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn descriptions(search_results: &SearchResult) -> Vec<&str> {
+    let mut descriptions: Vec<&str> = search_results
+        .todos
+        .iter()
+        .map(|todo| todo.description.as_str())
+        .collect();
+    descriptions.sort();
+    descriptions
+}
+
+#[test]
+fn find_todos_in_the_working_directory() {
+    let repository = fixture();
+    let search_results = search(repository.path(), SourceKind::WorkingDirectory);
+
+    assert_eq!(
+        descriptions(&search_results),
+        vec!["Staged TODO", "Untracked TODO"]
+    );
+}
+
+#[test]
+fn find_todos_in_a_revision() {
+    let repository = fixture();
+    let search_results = search(repository.path(), SourceKind::Revision("v1".to_string()));
+
+    assert_eq!(descriptions(&search_results), vec!["Committed TODO"]);
+    assert_eq!(search_results.todos[0].file, repository.path().join("a.rs"));
+}
+
+#[test]
+fn find_todos_in_the_index() {
+    let repository = fixture();
+    let search_results = search(repository.path(), SourceKind::Index);
+
+    assert_eq!(descriptions(&search_results), vec!["Staged TODO"]);
+}
+
+#[test]
+fn find_todos_with_the_gitattributes_of_the_tree() {
+    let repository = fixture();
+    let path = repository.path();
+    fs::write(
+        path.join(".gitattributes"),
+        "generated.rs linguist-generated\n",
+    )
+    .unwrap();
+    fs::write(
+        path.join("generated.rs"),
+        "// @todo(2222-01-01): Generated TODO\n",
+    )
+    .unwrap();
+    git(path, &["add", ".gitattributes", "generated.rs"]);
+    git(path, &["commit", "--quiet", "-m", "Add a generated file"]);
+    // Only the working directory no longer skips the generated file
+    fs::remove_file(path.join(".gitattributes")).unwrap();
+
+    for source in [SourceKind::Revision("HEAD".to_string()), SourceKind::Index] {
+        let search_results = search(path, source);
+        assert_eq!(descriptions(&search_results), vec!["Staged TODO"]);
+    }
+}
+
+#[test]
+fn find_relative_deadlines_of_staged_lines() {
+    let repository = fixture();
+    let path = repository.path();
+    fs::write(
+        path.join("d.rs"),
+        "// @todo(+1d): Committed relative TODO\n",
+    )
+    .unwrap();
+    git(path, &["add", "d.rs"]);
+    git(
+        path,
+        &[
+            "commit",
+            "--quiet",
+            "--date=2000-01-01T00:00:00",
+            "-m",
+            "Add a relative TODO",
+        ],
+    );
+    fs::write(
+        path.join("d.rs"),
+        "// @todo(+1d): Staged relative TODO\n// @todo(+1d): Committed relative TODO\n",
+    )
+    .unwrap();
+    git(path, &["add", "d.rs"]);
+    // The working directory is back to the commit, where the first line is the committed one
+    fs::write(
+        path.join("d.rs"),
+        "// @todo(+1d): Committed relative TODO\n",
+    )
+    .unwrap();
+
+    let search_results = search(path, SourceKind::Index);
+    let overdue = |description: &str| {
+        search_results
+            .todos
+            .iter()
+            .any(|todo| todo.description == description && matches!(todo.state, TodoState::Overdue))
+    };
+
+    // Blaming the working directory would anchor the staged line to the commit instead
+    assert!(overdue("Committed relative TODO"));
+    assert!(!overdue("Staged relative TODO"));
+}
+
+#[test]
+fn find_todos_with_the_conditions_of_the_tree() {
+    let repository = fixture();
+    let path = repository.path();
+    fs::create_dir(path.join("legacy")).unwrap();
+    fs::write(path.join("legacy/old.rs"), "fn old() {}\n").unwrap();
+    fs::write(
+        path.join("Cargo.toml"),
+        "[package]\nname = \"conditions\"\nversion = \"1.0.0\"\n",
+    )
+    .unwrap();
+    fs::write(
+        path.join("conditions.rs"),
+        "// @todo(version>=2.0.0): Version TODO\n\
+         // @todo(when-exists:new_api.rs): Exists TODO\n\
+         // @todo(when-missing:legacy/): Missing TODO\n",
+    )
+    .unwrap();
+    git(path, &["add", "legacy", "Cargo.toml", "conditions.rs"]);
+    git(path, &["commit", "--quiet", "-m", "Add conditions"]);
+    // Every condition only holds in the working directory
+    fs::write(
+        path.join("Cargo.toml"),
+        "[package]\nname = \"conditions\"\nversion = \"2.0.0\"\n",
+    )
+    .unwrap();
+    fs::write(path.join("new_api.rs"), "fn new() {}\n").unwrap();
+    fs::remove_dir_all(path.join("legacy")).unwrap();
+
+    let overdue = |source: SourceKind| {
+        let mut overdue: Vec<String> = search(path, source)
+            .todos
+            .into_iter()
+            .filter(|todo| matches!(todo.state, TodoState::Overdue))
+            .map(|todo| todo.description)
+            .collect();
+        overdue.sort();
+        overdue
+    };
+
+    assert_eq!(
+        overdue(SourceKind::WorkingDirectory),
+        vec!["Exists TODO", "Missing TODO", "Version TODO"]
+    );
+    assert!(overdue(SourceKind::Revision("HEAD".to_string())).is_empty());
+    assert!(overdue(SourceKind::Index).is_empty());
+}
+
+#[test]
+fn test_unknown_revisions_fail() {
+    let repository = fixture();
+
+    assert_cmd::Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--rev", "v2"])
+        .current_dir(repository.path())
+        .assert()
+        .code(todo_ci::exit_code::ERROR);
+}
+
+#[test]
+fn find_todos_in_listed_files() {
+    let repository = fixture();
+    let search_results = search(
+        repository.path(),
+        SourceKind::FileList(vec![
+            repository.path().join("ignored.rs"),
+            repository.path().join("c.rs"),
+        ]),
+    );
+
+    // Listed files are searched even if they are ignored
+    assert_eq!(
+        descriptions(&search_results),
+        vec!["Ignored TODO", "Untracked TODO"]
+    );
+}
+
+#[test]
+fn test_files_from_stdin() {
+    let repository = fixture();
+
+    assert_cmd::Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--files-from", "-", "-d", "concise"])
+        .write_stdin("b.rs\0c.rs\0")
+        .current_dir(repository.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2 todo(s) found"));
+}
+
+#[test]
+fn test_missing_listed_files_fail() {
+    let repository = fixture();
+
+    assert_cmd::Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--files-from", "-"])
+        .write_stdin("b.rs\nmissing.rs\n")
+        .current_dir(repository.path())
+        .assert()
+        .code(todo_ci::exit_code::ERROR);
+}
+
+#[test]
+fn test_stdin_is_reported_by_its_file_name() {
+    let repository = fixture();
+
+    assert_cmd::Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["-", "--stdin-filename", "src/unsaved.rs"])
+        .write_stdin("// @todo(2000-01-01): Unsaved TODO\n")
+        .current_dir(repository.path())
+        .assert()
+        .code(todo_ci::exit_code::OVERDUE)
+        .stdout(predicates::str::contains("src/unsaved.rs:1"))
+        .stdout(predicates::str::contains("Unsaved TODO"));
+}
+
+#[test]
+fn test_stdin_is_blamed_by_its_contents() {
+    let repository = fixture();
+    let path = repository.path();
+    fs::write(
+        path.join("d.rs"),
+        "// @todo(+1d): Committed relative TODO\n",
+    )
+    .unwrap();
+    git(path, &["add", "d.rs"]);
+    git(
+        path,
+        &[
+            "commit",
+            "--quiet",
+            "--date=2000-01-01T00:00:00",
+            "-m",
+            "Add a relative TODO",
+        ],
+    );
+
+    // Blaming the file on disk would anchor the unsaved line to the commit instead
+    assert_cmd::Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["-", "--stdin-filename", "d.rs", "-d", "overdue-only"])
+        .write_stdin(
+            "// @todo(+1d): Unsaved relative TODO\n// @todo(+1d): Committed relative TODO\n",
+        )
+        .current_dir(path)
+        .assert()
+        .code(todo_ci::exit_code::OVERDUE)
+        .stdout(predicates::str::contains("Committed relative TODO"))
+        .stdout(predicates::str::contains("Unsaved relative TODO").not());
+}
+
+#[test]
+fn test_stdin_requires_a_file_name() {
+    assert_cmd::Command::cargo_bin("todo-ci")
+        .unwrap()
+        .arg("-")
+        .write_stdin("// @todo(2000-01-01): Unsaved TODO\n")
+        .assert()
+        .code(todo_ci::exit_code::ERROR);
+}
@@ -0,0 +1,23 @@
+// todo-ci: ignore-next-line -- documents the annotation syntax
+// @todo(2000-01-01): Suppressed on the next line
+
+fn main() {
+    let _ = 1; /* todo-ci: ignore-line */ // @todo(2000-01-01): Suppressed on the same line
+}
+
+// todo-ci: disable -- examples of the annotation syntax
+// @todo(2000-01-01): Suppressed in a region
+// @todo(2000-13-01): Malformed, but suppressed in a region
+// todo-ci: enable
+
+// @todo(2000-01-01): Not suppressed
+// todo-ci: ignore-next-line
+fn unused() {}
+
+// Suppressions in strings are not suppressions
+const SYNTAX: &str = "todo-ci: ignore-next-line";
+// @todo(2000-01-01): Not suppressed either
+
+/// `todo-ci: ignore-next-line -- quoted in a doc comment`
+/// @todo(2000-01-01): Suppressed after a quoted suppression
+fn documented() {}
@@ -0,0 +1,5 @@
+# todo-ci: ignore-next-line -- documents the annotation syntax
+# @todo(2000-01-01): Suppressed with a reason
+
+# todo-ci: ignore-next-line
+# @todo(2000-01-01): Not suppressed without a reason
@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use todo_ci::{
+    config::Config,
+    core::{SearchOptions, TodoState},
+    suppression::Directive,
+};
+
+#[test]
+fn find_todos_with_inline_suppressions() {
+    let search_results = todo_ci::core::search(
+        Path::new("./tests/suppressions/fixtures/code.rs").to_path_buf(),
+        &SearchOptions::default(),
+    )
+    .unwrap();
+
+    let line_numbers: Vec<i32> = search_results
+        .todos
+        .iter()
+        .map(|todo| todo.line_number)
+        .collect();
+    assert_eq!(line_numbers, vec![13, 19]);
+
+    let suppressed_line_numbers: Vec<i32> = search_results
+        .suppressed_todos
+        .iter()
+        .map(|todo| todo.suppression.as_ref().unwrap().line_number)
+        .collect();
+    assert_eq!(suppressed_line_numbers, vec![1, 5, 8, 8, 21]);
+    assert!(matches!(
+        search_results.suppressed_todos[3].state,
+        TodoState::Malformed
+    ));
+
+    assert_eq!(search_results.statistics.overdue_todo_count, 2);
+    assert_eq!(search_results.statistics.malformed_todo_count, 0);
+    assert_eq!(search_results.statistics.suppressed_todo_count, 5);
+
+    let suppressions: Vec<(i32, Directive, bool)> = search_results
+        .suppressions
+        .iter()
+        .map(|suppression| {
+            (
+                suppression.line_number,
+                suppression.directive,
+                suppression.used,
+            )
+        })
+        .collect();
+    assert_eq!(
+        suppressions,
+        vec![
+            (1, Directive::IgnoreNextLine, true),
+            (5, Directive::IgnoreLine, true),
+            (8, Directive::Disable, true),
+            (11, Directive::Enable, true),
+            (14, Directive::IgnoreNextLine, false),
+            (21, Directive::IgnoreNextLine, true),
+        ]
+    );
+    assert_eq!(
+        search_results.suppressions[0].reason.as_deref(),
+        Some("documents the annotation syntax")
+    );
+    // Closing backticks of quoted suppressions aren't part of the reason
+    assert_eq!(
+        search_results.suppressions[5].reason.as_deref(),
+        Some("quoted in a doc comment")
+    );
+}
+
+#[test]
+fn find_todos_with_required_suppression_reasons() {
+    let root_directory = Path::new("./tests/suppressions/fixtures/require_reason");
+    let config = Config::load(root_directory, None).unwrap();
+    let search_results = todo_ci::core::search(
+        root_directory.to_path_buf(),
+        &SearchOptions {
+            suppression_policy: config.suppressions,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(search_results.todos.len(), 1);
+    assert_eq!(search_results.todos[0].line_number, 5);
+    assert_eq!(search_results.suppressed_todos.len(), 1);
+    assert!(!search_results.suppressions[0].missing_reason);
+    assert!(search_results.suppressions[1].missing_reason);
+}
+
+#[test]
+fn test_suppressions_in_output() {
+    let mut cmd = Command::cargo_bin("todo-ci").unwrap();
+    cmd.args(["--display-mode", "verbose", "code.rs"])
+        .current_dir("./tests/suppressions/fixtures")
+        .assert()
+        .stdout(predicates::str::contains("Suppressed on the next line"))
+        .stdout(predicates::str::contains(
+            "`todo-ci: disable` on code.rs:8 (examples of the annotation syntax)",
+        ))
+        .stdout(predicates::str::contains("(5 suppressed)"))
+        .stdout(predicates::str::contains("code.rs:14"));
+
+    let mut cmd = Command::cargo_bin("todo-ci").unwrap();
+    cmd.arg("code.rs")
+        .current_dir("./tests/suppressions/fixtures")
+        .assert()
+        .stdout(predicates::str::contains("Suppressed on the next line").not())
+        .stdout(predicates::str::contains("code.rs:14"));
+}
@@ -0,0 +1,258 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use assert_cmd::Command;
+use pretty_assertions::assert_eq;
+use todo_ci::core::{SearchOptions, SearchResult};
+
+fn write(path: PathBuf, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+/// Creates a Cargo workspace with a root package, two members and an excluded crate
+///
+/// Workspaces are created at runtime, so their manifests aren't mistaken for ones of this crate
+fn cargo_fixture() -> tempfile::TempDir {
+    let root_directory = tempfile::tempdir().unwrap();
+    let path = root_directory.path();
+
+    write(
+        path.join("Cargo.toml"),
+        "[package]\nname = \"root\"\n\n[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/excluded\"]\n",
+    );
+    write(
+        path.join("src/main.rs"),
+        "// @todo(2222-01-01): TODO of the root package\n",
+    );
+    write(
+        path.join("crates/a/Cargo.toml"),
+        "[package]\nname = \"a\"\n",
+    );
+    write(
+        path.join("crates/a/src/lib.rs"),
+        "// @todo(2000-01-01): Overdue TODO of a\n",
+    );
+    write(
+        path.join("crates/b/Cargo.toml"),
+        "[package]\nname = \"b\"\n",
+    );
+    write(
+        path.join("crates/b/src/lib.rs"),
+        "// @todo(2222-01-01): TODO of b\n",
+    );
+    write(
+        path.join("crates/excluded/Cargo.toml"),
+        "[package]\nname = \"excluded\"\n",
+    );
+
+    root_directory
+}
+
+fn search(packages: &[PathBuf]) -> SearchResult {
+    todo_ci::core::search_packages(
+        packages
+            .iter()
+            .map(|package| (package.clone(), SearchOptions::default()))
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn relative_packages(root_directory: &Path, packages: &[PathBuf]) -> Vec<PathBuf> {
+    packages
+        .iter()
+        .map(|package| package.strip_prefix(root_directory).unwrap().to_path_buf())
+        .collect()
+}
+
+#[test]
+fn read_cargo_workspace_members() {
+    let root_directory = cargo_fixture();
+    let packages = todo_ci::workspace::packages(root_directory.path()).unwrap();
+
+    assert_eq!(
+        relative_packages(root_directory.path(), &packages),
+        vec![
+            PathBuf::from(""),
+            PathBuf::from("crates/a"),
+            PathBuf::from("crates/b")
+        ]
+    );
+}
+
+#[test]
+fn read_npm_and_pnpm_workspace_packages() {
+    let root_directory = tempfile::tempdir().unwrap();
+    let path = root_directory.path();
+    write(
+        path.join("package.json"),
+        r#"{ "private": true, "workspaces": ["apps/*", "!apps/legacy"] }"#,
+    );
+    write(
+        path.join("pnpm-workspace.yaml"),
+        "packages:\n  - 'libs/**'\n  - \"!**/fixtures/**\"\n",
+    );
+    for package in [
+        "apps/web",
+        "apps/legacy",
+        "libs/ui",
+        "libs/ui/fixtures/demo",
+    ] {
+        write(path.join(package).join("package.json"), "{}");
+    }
+    write(path.join("apps/docs/README.md"), "Not a package\n");
+
+    let packages = todo_ci::workspace::packages(path).unwrap();
+
+    assert_eq!(
+        relative_packages(path, &packages),
+        vec![
+            PathBuf::from(""),
+            PathBuf::from("apps/web"),
+            PathBuf::from("libs/ui")
+        ]
+    );
+}
+
+#[test]
+fn search_files_of_no_member_in_virtual_workspaces() {
+    let root_directory = tempfile::tempdir().unwrap();
+    let path = root_directory.path();
+    write(
+        path.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]\n",
+    );
+    write(
+        path.join("scripts/release.sh"),
+        "# @todo(2222-01-01): TODO of no member\n",
+    );
+    // Member paths are excluded from the root literally, not as globs
+    write(
+        path.join("crates/[lib]/Cargo.toml"),
+        "[package]\nname = \"lib\"\n",
+    );
+    write(
+        path.join("crates/[lib]/src/lib.rs"),
+        "// @todo(2222-01-01): TODO of [lib]\n",
+    );
+    write(
+        path.join("crates/l/src/lib.rs"),
+        "// @todo(2222-01-01): TODO of no member either\n",
+    );
+
+    let packages = todo_ci::workspace::packages(path).unwrap();
+    assert_eq!(
+        relative_packages(path, &packages),
+        vec![PathBuf::from(""), PathBuf::from("crates/[lib]")]
+    );
+
+    let search_results = search(&packages);
+    let package_counts: Vec<i32> = search_results
+        .packages
+        .iter()
+        .map(|package| package.statistics.valid_todo_count)
+        .collect();
+    assert_eq!(package_counts, vec![2, 1]);
+}
+
+#[test]
+fn test_missing_workspaces_fail() {
+    let root_directory = tempfile::tempdir().unwrap();
+
+    assert!(todo_ci::workspace::packages(root_directory.path()).is_err());
+}
+
+#[test]
+fn package_statistics_roll_up_into_totals() {
+    let root_directory = cargo_fixture();
+    let packages = todo_ci::workspace::packages(root_directory.path()).unwrap();
+    let search_results = search(&packages);
+
+    let package_counts: Vec<(i32, i32)> = search_results
+        .packages
+        .iter()
+        .map(|package| {
+            (
+                package.statistics.valid_todo_count,
+                package.statistics.overdue_todo_count,
+            )
+        })
+        .collect();
+    // Members are left out of the root package
+    assert_eq!(package_counts, vec![(1, 0), (0, 1), (1, 0)]);
+    assert_eq!(search_results.statistics.valid_todo_count, 2);
+    assert_eq!(search_results.statistics.overdue_todo_count, 1);
+    assert_eq!(
+        search_results.statistics.files_searched,
+        search_results
+            .packages
+            .iter()
+            .map(|package| package.statistics.files_searched)
+            .sum::<i32>()
+    );
+    assert_eq!(
+        search_results.todos[1].package.as_deref(),
+        Some(packages[1].as_path())
+    );
+}
+
+#[test]
+fn members_use_the_lockfile_and_fields_of_the_workspace() {
+    let root_directory = tempfile::tempdir().unwrap();
+    let path = root_directory.path();
+    write(
+        path.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"2.0.0\"\nrust-version = \"1.70\"\n",
+    );
+    write(
+        path.join("Cargo.lock"),
+        "[[package]]\nname = \"serde\"\nversion = \"1.0.200\"\n",
+    );
+    write(
+        path.join("crates/a/Cargo.toml"),
+        "[package]\nname = \"a\"\nversion.workspace = true\nrust-version = { workspace = true }\n",
+    );
+    write(
+        path.join("crates/a/src/lib.rs"),
+        "// @todo(version>=2.0.0): Inherited version\n// @todo(rust>=1.70): Inherited Rust version\n// @todo(dep:serde>=1.0.200): Dependency of the workspace lockfile\n",
+    );
+
+    let packages = todo_ci::workspace::packages(path).unwrap();
+    let search_results = search(&packages);
+
+    assert_eq!(search_results.statistics.malformed_todo_count, 0);
+    assert_eq!(search_results.statistics.overdue_todo_count, 3);
+}
+
+#[test]
+fn test_several_root_directories_are_reported_together() {
+    let root_directory = cargo_fixture();
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["crates/a", "crates/b", "-d", "concise"])
+        .current_dir(root_directory.path())
+        .assert()
+        .code(todo_ci::exit_code::OVERDUE)
+        .stdout(predicates::str::contains("2 todo(s) found"))
+        .stdout(predicates::str::contains(
+            ": 2 file(s), 1 todo(s), 1 overdue",
+        ));
+}
+
+#[test]
+fn test_workspace_is_reported_by_package() {
+    let root_directory = cargo_fixture();
+
+    Command::cargo_bin("todo-ci")
+        .unwrap()
+        .args(["--workspace", "--no-error"])
+        .current_dir(root_directory.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Package ./crates/a:"))
+        .stdout(predicates::str::contains("3 todo(s) found"));
+}